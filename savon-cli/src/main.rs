@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use clap::Parser;
+use savon::gen::GenError;
 use std::path::Path;
 
 #[derive(Parser, Debug)]
@@ -12,6 +13,18 @@ pub struct Args {
     /// Output Rust file (Will create next to input if not provided)
     #[arg(short, long, required = false)]
     pub output: Option<String>,
+
+    /// Input is a standalone XML schema, only its types are generated
+    #[arg(long)]
+    pub xsd: bool,
+
+    /// Operation to generate a client method for, all of them if not provided
+    #[arg(long = "operation")]
+    pub operations: Vec<String>,
+
+    /// Only generate the types used by the operations, listing the dropped ones
+    #[arg(long)]
+    pub prune: bool,
 }
 
 fn main() -> Result<()> {
@@ -41,8 +54,31 @@ fn main() -> Result<()> {
 
     let mut data = Vec::new();
     input.read_to_end(&mut data)?;
-    let wsdl = savon::wsdl::parse(&data).unwrap();
-    let gen = savon::gen::gen(&wsdl).unwrap();
+    let gen = if args.xsd {
+        let xsd = savon::xsd::parse(&data)
+            .map_err(GenError::from)
+            .context("Failed to parse input schema")?;
+        savon::gen::gen_types(&xsd, &Default::default()).context("Failed to generate types")?
+    } else {
+        let wsdl = savon::wsdl::parse(&data)
+            .map_err(GenError::from)
+            .context("Failed to parse input WSDL")?;
+        let mut generator = savon::gen::Generator::new().prune_types(args.prune);
+        if !args.operations.is_empty() {
+            generator = generator.operations(args.operations);
+        }
+        if args.prune {
+            for name in generator
+                .unused_types(&wsdl)
+                .context("Failed to find unused types")?
+            {
+                eprintln!("dropped unused type {name}");
+            }
+        }
+        generator
+            .generate(&wsdl)
+            .context("Failed to generate code")?
+    };
     let fmt = prettyplease::unparse(&syn::parse_quote!(#gen));
 
     output
@@ -0,0 +1,3921 @@
+use crate::ident::{self, Scope};
+use crate::string;
+use crate::wsdl::{
+    parse, ComplexType, Message, MessageContent, Operation, Part, Port, PortType,
+    QualifiedTypename, Schema, SimpleType, Transmission, Type, TypeAttribute, Wsdl,
+};
+use crate::xsd::Xsd;
+use proc_macro2::{Ident, Literal, TokenStream};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Generates `#[doc]` attributes for the given paragraphs, skipping `None`s.
+fn gen_doc(paragraphs: &[Option<&str>]) -> TokenStream {
+    let paragraphs = paragraphs.iter().flatten().collect::<Vec<_>>();
+    let lines = paragraphs
+        .iter()
+        .enumerate()
+        .flat_map(|(i, paragraph)| (i > 0).then_some("").into_iter().chain(paragraph.lines()))
+        .map(|line| match line {
+            "" => String::new(),
+            line => format!(" {}", line),
+        });
+
+    quote! { #(#[doc = #lines])* }
+}
+
+/// Code generation options.
+#[derive(Clone, Debug, Default)]
+pub struct GenOptions {
+    /// Represent repeated fields with `minOccurs` of 1 or more, or a finite
+    /// `maxOccurs`, as `savon::bounded::BoundedVec` instead of `Vec`.
+    pub bounded_vecs: bool,
+    /// Match decoded elements on their local name only, for servers getting
+    /// namespaces wrong.
+    pub lenient_namespaces: bool,
+    /// Traits derived by every generated type, besides `Clone`, `Debug` and
+    /// `Default`, e.g. `PartialEq`.
+    pub derives: Vec<String>,
+    /// Visibility of the generated items and fields, `pub` if not set.
+    pub visibility: Option<String>,
+    /// Prepended to the names of the schema types.
+    pub type_prefix: String,
+    /// Appended to the names of the schema types.
+    pub type_suffix: String,
+    /// Operations given a client method, by name, all of them if not set.
+    pub operations: Option<Vec<String>>,
+    /// Schema types generated, by local name, along with the types they
+    /// reference, all of them if not set. Messages of other types are skipped.
+    pub types: Option<Vec<String>>,
+    /// Only generate the types reachable from the operations given a client
+    /// method, and the messages of these operations. Dropped types are logged,
+    /// see `Generator::unused_types`.
+    pub prune_types: bool,
+    /// Generate blocking clients, using `savon::http::blocking`, instead of
+    /// async ones.
+    pub blocking: bool,
+    /// Path of the savon crate in the generated code, `savon` if not set.
+    pub crate_path: Option<String>,
+    /// Derive `serde::Serialize` and `Deserialize`, fields and variants being
+    /// renamed to their XML names. The generated code then requires the
+    /// `serde` feature of savon. Wildcard elements are skipped.
+    pub serde: bool,
+    /// Rust names replacing the converted ones, by XML name: `Type` for a
+    /// schema type, message, service or port, `Type.field` for a field of a
+    /// complex type, and `PortType.operation` for a client method. Renamed
+    /// types get no prefix or suffix.
+    pub renames: BTreeMap<String, String>,
+    /// Rust types replacing schema types in the elements and attributes of
+    /// complex types, by `Type.field` for a field, `{namespace}Type` or
+    /// `xsd:type` for a qualified type, or the local name of a schema type,
+    /// in that order of precedence. Mapped types must implement the traits
+    /// derived by the generated types, `Default` included unless they are
+    /// only used by optional or repeated fields.
+    pub type_mappings: BTreeMap<String, TypeMapping>,
+    /// Generate a `{Type}Builder` for every complex type without mixed
+    /// content, whose `build` method fails if a required field is not set.
+    pub builders: bool,
+    /// Generate a `{Client}Api` trait of the client methods of every client,
+    /// implemented by the client, for substituting fakes in tests.
+    pub client_traits: bool,
+}
+
+/// A Rust type replacing a schema type, see `GenOptions::type_mappings`.
+#[derive(Clone, Debug)]
+pub struct TypeMapping {
+    /// Path of the Rust type, e.g. `uuid::Uuid`.
+    pub rust_type: String,
+    pub conversion: Conversion,
+}
+
+/// How the values of a mapped type are read and written.
+#[derive(Clone, Debug)]
+pub enum Conversion {
+    /// As text, with `FromStr` and `Display`.
+    FromStr,
+    /// As text, with the `savon::gen::Convert` implementation of the
+    /// converter at this path, for types without `FromStr` and `Display`
+    /// implementations in the XML format.
+    Converter(String),
+    /// As the content of an element, with the `savon::gen::FromElement` and
+    /// `ToElements` implementations of the type. Attributes keep their schema
+    /// type.
+    Element,
+}
+
+impl GenOptions {
+    /// The rename of `key`, if any, otherwise the `converted` name.
+    fn renamed(&self, key: &str, converted: impl FnOnce() -> String) -> String {
+        self.renames.get(key).cloned().unwrap_or_else(converted)
+    }
+}
+
+#[derive(Debug)]
+pub enum GenError {
+    Io(std::io::Error),
+    Wsdl(crate::wsdl::WsdlError),
+    /// An option is not valid Rust, e.g. a derive which is not a path.
+    InvalidOption {
+        option: &'static str,
+        value: String,
+    },
+}
+
+impl From<std::io::Error> for GenError {
+    fn from(e: std::io::Error) -> Self {
+        GenError::Io(e)
+    }
+}
+
+impl From<crate::wsdl::WsdlError> for GenError {
+    fn from(e: crate::wsdl::WsdlError) -> Self {
+        GenError::Wsdl(e)
+    }
+}
+
+impl std::fmt::Display for GenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenError::Io(e) => write!(f, "I/O error: {e}"),
+            GenError::Wsdl(e) => write!(f, "invalid WSDL: {e:?}"),
+            GenError::InvalidOption { option, value } => {
+                write!(f, "invalid {option} option: `{value}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GenError {}
+
+/// Parses the value of an option as a `T`.
+fn parse_option<T: syn::parse::Parse>(option: &'static str, value: &str) -> Result<T, GenError> {
+    syn::parse_str(value).map_err(|_| GenError::InvalidOption {
+        option,
+        value: value.to_string(),
+    })
+}
+
+/// Builder generating the code of WSDL or XSD documents, for build scripts
+/// which generate several of them, or need more than the defaults:
+///
+/// ```no_run
+/// # fn main() -> Result<(), savon_gen::gen::GenError> {
+/// let out_dir = std::env::var("OUT_DIR").unwrap();
+/// savon_gen::gen::Generator::new()
+///     .output("countries.rs")
+///     .derive("PartialEq")
+///     .blocking(true)
+///     .generate_file("countries.wsdl", &out_dir)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Generator {
+    options: GenOptions,
+    output: Option<String>,
+    module: Option<String>,
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name of the file written by `generate_file`, the snake case stem of
+    /// the input file if not set.
+    pub fn output(mut self, file_name: &str) -> Self {
+        self.output = Some(file_name.to_string());
+        self
+    }
+
+    /// Wraps the generated code in a module of that name.
+    pub fn module(mut self, name: &str) -> Self {
+        self.module = Some(name.to_string());
+        self
+    }
+
+    /// Adds a trait derived by every generated type.
+    pub fn derive(mut self, derive: &str) -> Self {
+        self.options.derives.push(derive.to_string());
+        self
+    }
+
+    /// Visibility of the generated items and fields, e.g. `pub(crate)`.
+    pub fn visibility(mut self, visibility: &str) -> Self {
+        self.options.visibility = Some(visibility.to_string());
+        self
+    }
+
+    pub fn type_prefix(mut self, prefix: &str) -> Self {
+        self.options.type_prefix = prefix.to_string();
+        self
+    }
+
+    pub fn type_suffix(mut self, suffix: &str) -> Self {
+        self.options.type_suffix = suffix.to_string();
+        self
+    }
+
+    /// Only generates client methods for these operations.
+    pub fn operations<I: IntoIterator<Item = S>, S: Into<String>>(mut self, names: I) -> Self {
+        self.options.operations = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Only generates these schema types, and the types they reference.
+    pub fn types<I: IntoIterator<Item = S>, S: Into<String>>(mut self, names: I) -> Self {
+        self.options.types = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn blocking(mut self, blocking: bool) -> Self {
+        self.options.blocking = blocking;
+        self
+    }
+
+    /// Path of the savon crate, for crates which rename or re-export it.
+    pub fn crate_path(mut self, path: &str) -> Self {
+        self.options.crate_path = Some(path.to_string());
+        self
+    }
+
+    /// Derives serde traits, see `GenOptions::serde`.
+    pub fn serde(mut self, serde: bool) -> Self {
+        self.options.serde = serde;
+        self
+    }
+
+    /// Maps a schema type or field to a Rust type, see
+    /// `GenOptions::type_mappings`:
+    ///
+    /// ```no_run
+    /// # use savon_gen::gen::{Conversion, Generator};
+    /// let generator = Generator::new()
+    ///     .map_type("{http://tempuri.org/}Guid", "uuid::Uuid", Conversion::FromStr)
+    ///     .map_type(
+    ///         "xsd:dateTime",
+    ///         "time::OffsetDateTime",
+    ///         Conversion::Converter("crate::xml::Rfc3339".to_string()),
+    ///     )
+    ///     .map_type("Order.customer", "crate::Customer", Conversion::Element);
+    /// ```
+    pub fn map_type(mut self, key: &str, rust_type: &str, conversion: Conversion) -> Self {
+        self.options.type_mappings.insert(
+            key.to_string(),
+            TypeMapping {
+                rust_type: rust_type.to_string(),
+                conversion,
+            },
+        );
+        self
+    }
+
+    /// Names the item `name` refers to `to`, see `GenOptions::renames`.
+    pub fn rename(mut self, name: &str, to: &str) -> Self {
+        self.options
+            .renames
+            .insert(name.to_string(), to.to_string());
+        self
+    }
+
+    /// Only generates the types used by the operations, see
+    /// `GenOptions::prune_types`.
+    pub fn prune_types(mut self, prune_types: bool) -> Self {
+        self.options.prune_types = prune_types;
+        self
+    }
+
+    /// Generates builders, see `GenOptions::builders`.
+    pub fn builders(mut self, builders: bool) -> Self {
+        self.options.builders = builders;
+        self
+    }
+
+    /// Generates client traits, see `GenOptions::client_traits`.
+    pub fn client_traits(mut self, client_traits: bool) -> Self {
+        self.options.client_traits = client_traits;
+        self
+    }
+
+    pub fn bounded_vecs(mut self, bounded_vecs: bool) -> Self {
+        self.options.bounded_vecs = bounded_vecs;
+        self
+    }
+
+    pub fn lenient_namespaces(mut self, lenient_namespaces: bool) -> Self {
+        self.options.lenient_namespaces = lenient_namespaces;
+        self
+    }
+
+    pub fn options(&self) -> &GenOptions {
+        &self.options
+    }
+
+    /// Schema types of a WSDL document which are not reachable from the
+    /// operations given a client method, and are dropped when pruning.
+    pub fn unused_types(&self, wsdl: &Wsdl) -> Result<Vec<QualifiedTypename>, GenError> {
+        let ctx = Context::new(&wsdl.types, &wsdl.schemas, &self.options)?;
+        let reachable = reachable_types(wsdl, &ctx);
+        Ok(wsdl
+            .types
+            .keys()
+            .filter(|name| !reachable.contains(*name))
+            .cloned()
+            .collect())
+    }
+
+    /// Generates the types, messages and clients of a WSDL document.
+    pub fn generate(&self, wsdl: &Wsdl) -> Result<TokenStream, GenError> {
+        let generated = gen_with_options(wsdl, &self.options)?;
+        self.wrap(generated)
+    }
+
+    /// Generates the types of a standalone XML schema.
+    pub fn generate_types(&self, xsd: &Xsd) -> Result<TokenStream, GenError> {
+        let generated = gen_types(xsd, &self.options)?;
+        self.wrap(generated)
+    }
+
+    /// Generates the code of the WSDL document, or XML schema if its
+    /// extension is `.xsd`, at `input` into a file of `out_dir`, returning
+    /// its path.
+    pub fn generate_file(
+        &self,
+        input: impl AsRef<Path>,
+        out_dir: impl AsRef<Path>,
+    ) -> Result<PathBuf, GenError> {
+        let input = input.as_ref();
+        let generated = self.wrap(self.parts(input)?.single())?;
+
+        let out_path = out_dir.as_ref().join(self.output_name(input));
+        write_formatted(&generated, &out_path)?;
+
+        Ok(out_path)
+    }
+
+    /// Generates the code of the WSDL document, or XML schema if its
+    /// extension is `.xsd`, at `input` into a directory of `out_dir`, named
+    /// like the file `generate_file` writes without its extension, returning
+    /// the path of its `mod.rs`. Types are split by namespace into
+    /// `types/{namespace}.rs`, messages and clients are written to
+    /// `messages.rs` and `client.rs`, and `mod.rs` re-exports all of them:
+    ///
+    /// ```ignore
+    /// mod soap {
+    ///     include!(concat!(env!("OUT_DIR"), "/countries/mod.rs"));
+    /// }
+    /// ```
+    ///
+    /// Files which are no longer generated, e.g. of a removed namespace, are
+    /// removed.
+    pub fn generate_dir(
+        &self,
+        input: impl AsRef<Path>,
+        out_dir: impl AsRef<Path>,
+    ) -> Result<PathBuf, GenError> {
+        let input = input.as_ref();
+        let mut files = self.parts(input)?.files();
+        let root = files.remove(Path::new("mod.rs")).unwrap_or_default();
+        files.insert(PathBuf::from("mod.rs"), self.wrap(root)?);
+
+        let output = self.output_name(input);
+        let dir = out_dir.as_ref().join(Path::new(&output).with_extension(""));
+        std::fs::create_dir_all(dir.join("types"))?;
+        for entry in std::fs::read_dir(&dir)?.chain(std::fs::read_dir(dir.join("types"))?) {
+            let path = entry?.path();
+            let stale = path.extension().is_some_and(|e| e == "rs")
+                && !files.contains_key(path.strip_prefix(&dir).unwrap_or(&path));
+            if stale {
+                std::fs::remove_file(path)?;
+            }
+        }
+        for (path, generated) in files.iter() {
+            write_formatted(generated, &dir.join(path))?;
+        }
+
+        Ok(dir.join("mod.rs"))
+    }
+
+    /// The generated code of the WSDL document or XML schema at `input`.
+    fn parts(&self, input: &Path) -> Result<Parts, GenError> {
+        let data = std::fs::read(input)?;
+        if input.extension().is_some_and(|e| e == "xsd") {
+            gen_types_parts(&crate::xsd::parse(&data[..])?, &self.options)
+        } else {
+            gen_parts(&parse(&data[..])?, &self.options)
+        }
+    }
+
+    /// Name of the file written by `generate_file`.
+    fn output_name(&self, input: &Path) -> String {
+        match &self.output {
+            Some(output) => output.clone(),
+            None => {
+                let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+                format!("{}.rs", string::to_snake(&stem))
+            }
+        }
+    }
+
+    fn wrap(&self, generated: TokenStream) -> Result<TokenStream, GenError> {
+        Ok(match &self.module {
+            Some(module) => {
+                let module: Ident = parse_option("module", module)?;
+                let vis = gen_visibility(&self.options)?;
+                quote! {
+                    #vis mod #module {
+                        #generated
+                    }
+                }
+            }
+            None => generated,
+        })
+    }
+}
+
+/// Formats the generated code and writes it to `path`.
+fn write_formatted(generated: &TokenStream, path: &Path) -> Result<(), GenError> {
+    let file = syn::parse2(generated.clone())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+    let formatted = prettyplease::unparse(&file);
+
+    let mut output = File::create(path)?;
+    output.write_all(formatted.as_bytes())?;
+    output.flush()?;
+
+    Ok(())
+}
+
+fn gen_visibility(options: &GenOptions) -> Result<TokenStream, GenError> {
+    let vis = options.visibility.as_deref().unwrap_or("pub");
+    let vis: syn::Visibility = parse_option("visibility", vis)?;
+    Ok(quote! { #vis })
+}
+
+/// Statements opening the generated code: imports used by the generated
+/// items, and the alias of the savon crate if it has another path.
+fn gen_prelude(options: &GenOptions) -> Result<TokenStream, GenError> {
+    let alias = match options.crate_path.as_deref() {
+        None | Some("savon") | Some("::savon") => None,
+        Some(path) => {
+            let path: syn::Path = parse_option("crate path", path)?;
+            Some(quote! {
+                #[allow(unused_imports)]
+                use #path as savon;
+            })
+        }
+    };
+
+    Ok(quote! {
+        #alias
+        #[allow(unused_imports)]
+        use savon::internal::xmltree;
+        #[allow(unused_imports)]
+        use savon::rpser::xml::*;
+    })
+}
+
+const XSD_NS: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// State shared by the generation of all types.
+struct Context<'a> {
+    options: &'a GenOptions,
+    /// Visibility of the generated items and fields.
+    vis: TokenStream,
+    /// Traits derived by the generated types besides the default ones,
+    /// serde's included, followed by a comma.
+    derives: TokenStream,
+    /// Concrete types which may occur in place of a type with derived types,
+    /// represented by a `{Type}Kind` enum over them.
+    variants: BTreeMap<QualifiedTypename, Vec<QualifiedTypename>>,
+    /// Documentation of the types.
+    documentation: BTreeMap<QualifiedTypename, &'a str>,
+    /// Rust names of the types, unique within the generated module.
+    type_names: BTreeMap<QualifiedTypename, Ident>,
+    /// Names of the `{Type}Kind` enums of the types with derived types.
+    kind_names: BTreeMap<QualifiedTypename, Ident>,
+    /// Names of the `{Type}Segment` enums of the types with mixed content.
+    segment_names: BTreeMap<QualifiedTypename, Ident>,
+    /// Names of the `{Type}Builder` structs, if builders are generated.
+    builder_names: BTreeMap<QualifiedTypename, Ident>,
+    /// Names taken by the types, the names of messages, clients and fault
+    /// enums are taken from it.
+    scope: Scope,
+    /// Type mappings, by key.
+    mappings: BTreeMap<&'a str, Mapping>,
+}
+
+/// A type mapping, validated.
+struct Mapping {
+    rust_type: TokenStream,
+    /// Converter of the values from and to text, `None` for element content.
+    converter: Option<TokenStream>,
+}
+
+impl Mapping {
+    /// The `Convert` implementation of text conversions.
+    fn convert(&self) -> Option<TokenStream> {
+        let rust_type = &self.rust_type;
+        self.converter
+            .as_ref()
+            .map(|converter| quote! { <#converter as savon::gen::Convert<#rust_type>> })
+    }
+}
+
+/// Names the generated code refers to, which generated types may not shadow.
+const RESERVED_TYPE_NAMES: &[&str] = &[
+    "Box", "Default", "Err", "None", "Ok", "Option", "Result", "Self", "Some", "String", "Vec",
+    "savon", "std", "xmltree",
+];
+
+impl<'a> Context<'a> {
+    fn new(
+        types: &BTreeMap<QualifiedTypename, Type>,
+        schemas: &'a [Schema],
+        options: &'a GenOptions,
+    ) -> Result<Self, GenError> {
+        let derives = options
+            .derives
+            .iter()
+            .filter(|d| !matches!(d.as_str(), "Clone" | "Debug" | "Default"))
+            .map(|d| parse_option::<syn::Path>("derive", d))
+            .collect::<Result<Vec<_>, _>>()?;
+        for affix in [&options.type_prefix, &options.type_suffix] {
+            if !affix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(GenError::InvalidOption {
+                    option: "type name affix",
+                    value: affix.clone(),
+                });
+            }
+        }
+
+        let mappings = options
+            .type_mappings
+            .iter()
+            .map(|(key, mapping)| {
+                let rust_type: syn::Type = parse_option("type mapping", &mapping.rust_type)?;
+                let converter = match &mapping.conversion {
+                    Conversion::FromStr => Some(quote! { savon::gen::DisplayFromStr }),
+                    Conversion::Converter(path) => {
+                        let path: syn::Path = parse_option("converter", path)?;
+                        Some(quote! { #path })
+                    }
+                    Conversion::Element => None,
+                };
+                let mapping = Mapping {
+                    rust_type: quote! { #rust_type },
+                    converter,
+                };
+                Ok((key.as_str(), mapping))
+            })
+            .collect::<Result<_, GenError>>()?;
+
+        let complex = |name: &QualifiedTypename| match types.get(name) {
+            Some(Type::Complex(c)) => Some(c),
+            _ => None,
+        };
+
+        let mut variants = BTreeMap::<QualifiedTypename, Vec<QualifiedTypename>>::new();
+        for (name, t) in types.iter() {
+            let Type::Complex(c) = t else { continue };
+            if c.is_abstract {
+                continue;
+            }
+
+            let mut ancestors = vec![];
+            let mut base = c.base.as_ref();
+            while let Some(b) = base.filter(|b| *b != name && !ancestors.contains(b)) {
+                ancestors.push(b);
+                base = complex(b).and_then(|b| b.base.as_ref());
+            }
+            for ancestor in ancestors {
+                variants
+                    .entry(ancestor.clone())
+                    .or_default()
+                    .push(name.clone());
+            }
+        }
+
+        for (name, derived) in variants.iter_mut() {
+            if complex(name).map(|c| !c.is_abstract).unwrap_or(false) {
+                derived.insert(0, name.clone());
+            }
+        }
+
+        let documentation = schemas
+            .iter()
+            .flat_map(|schema| {
+                schema.documentation.iter().map(|(name, documentation)| {
+                    (
+                        QualifiedTypename::new(&schema.target_namespace, name),
+                        documentation.as_str(),
+                    )
+                })
+            })
+            .collect();
+
+        let serde = options.serde.then(|| {
+            quote! { savon::internal::serde::Serialize, savon::internal::serde::Deserialize, }
+        });
+
+        // concrete types are named first, then the enums and builders
+        // generated for them
+        let type_name = |name: &QualifiedTypename| {
+            options.renamed(name.name(), || {
+                format!(
+                    "{}{}{}",
+                    options.type_prefix,
+                    string::to_pascal(name.name()),
+                    options.type_suffix
+                )
+            })
+        };
+        let mut scope = Scope::new(RESERVED_TYPE_NAMES);
+        let type_names = types
+            .keys()
+            .map(|name| (name.clone(), scope.ident(&type_name(name))))
+            .collect();
+        let kind_names = variants
+            .keys()
+            .map(|name| {
+                let kind_name = scope.ident(&format!("{}Kind", type_name(name)));
+                (name.clone(), kind_name)
+            })
+            .collect();
+        let segment_names = types
+            .iter()
+            .filter(|(_, t)| matches!(t, Type::Complex(c) if c.mixed))
+            .map(|(name, _)| {
+                let segment_name = scope.ident(&format!("{}Segment", type_name(name)));
+                (name.clone(), segment_name)
+            })
+            .collect();
+        let builder_names = types
+            .iter()
+            .filter(|_| options.builders)
+            .filter(|(_, t)| matches!(t, Type::Complex(c) if !c.mixed))
+            .map(|(name, _)| {
+                let builder_name = scope.ident(&format!("{}Builder", type_name(name)));
+                (name.clone(), builder_name)
+            })
+            .collect();
+
+        Ok(Context {
+            options,
+            vis: gen_visibility(options)?,
+            derives: quote! { #(#derives,)* #serde },
+            variants,
+            documentation,
+            type_names,
+            kind_names,
+            segment_names,
+            builder_names,
+            scope,
+            mappings,
+        })
+    }
+
+    /// The mapping of the field or attribute `field` of type `ty` in the
+    /// complex type `name`, if any.
+    fn mapping(&self, name: &QualifiedTypename, field: &str, ty: &SimpleType) -> Option<&Mapping> {
+        let (namespace, local) = match ty {
+            SimpleType::Complex(n) => (n.namespace(), n.name()),
+            SimpleType::Boolean => (XSD_NS, "boolean"),
+            SimpleType::String => (XSD_NS, "string"),
+            SimpleType::Float => (XSD_NS, "float"),
+            SimpleType::Int => (XSD_NS, "int"),
+            SimpleType::DateTime => (XSD_NS, "dateTime"),
+            SimpleType::Base64Binary => (XSD_NS, "base64Binary"),
+        };
+        let type_keys = [
+            format!("{{{namespace}}}{local}"),
+            if namespace == XSD_NS {
+                format!("xsd:{local}")
+            } else {
+                local.to_string()
+            },
+        ];
+
+        std::iter::once(format!("{}.{field}", name.name()))
+            .chain(type_keys)
+            .find_map(|key| self.mappings.get(key.as_str()))
+    }
+
+    /// A `#[serde(...)]` attribute, if serde traits are derived.
+    fn serde_attr(&self, args: TokenStream) -> Option<TokenStream> {
+        self.options.serde.then(|| quote! { #[serde(#args)] })
+    }
+
+    /// The `#[serde(...)]` attribute of a type, pointing serde to its
+    /// re-export by savon, if serde traits are derived.
+    fn serde_container(&self, args: TokenStream) -> Option<TokenStream> {
+        self.serde_attr(quote! { crate = "savon::internal::serde", #args })
+    }
+
+    /// The Rust type of a schema type, regardless of its derived types.
+    /// Types which are not declared, such as unsupported builtin types, are
+    /// named after their local name.
+    fn concrete_ident(&self, name: &QualifiedTypename) -> Ident {
+        match self.type_names.get(name) {
+            Some(ident) => ident.clone(),
+            None => ident::ident(&string::to_pascal(name.name())),
+        }
+    }
+
+    /// The `{Type}Segment` enum of a type with mixed content.
+    fn segment_ident(&self, name: &QualifiedTypename) -> Ident {
+        self.segment_names[name].clone()
+    }
+
+    /// Documentation of a generated type: the schema documentation, if any,
+    /// and its qualified name.
+    fn type_doc(&self, name: &QualifiedTypename) -> TokenStream {
+        gen_doc(&[
+            self.documentation.get(name).copied(),
+            Some(&format!("Qualified type: {}", name)),
+        ])
+    }
+
+    /// The Rust type of a complex type, its `{Type}Kind` enum if it has derived types.
+    fn type_ident(&self, name: &QualifiedTypename) -> Ident {
+        match self.kind_names.get(name) {
+            Some(kind_name) => kind_name.clone(),
+            None => self.concrete_ident(name),
+        }
+    }
+}
+
+/// Generates the Rust type of `ty`, or the type it is mapped to.
+fn gen_simple(ty: &SimpleType, mapping: Option<&Mapping>, ctx: &Context) -> TokenStream {
+    if let Some(mapping) = mapping {
+        return mapping.rust_type.clone();
+    }
+
+    match ty {
+        SimpleType::Boolean => quote! { bool },
+        SimpleType::String => quote! { String },
+        SimpleType::Float => quote! { f64 },
+        SimpleType::Int => quote! { i64 },
+        SimpleType::DateTime => {
+            quote! { savon::internal::chrono::DateTime<savon::internal::chrono::offset::Utc> }
+        }
+        SimpleType::Base64Binary => quote! { String }, // TODO: Base64 type...
+        SimpleType::Complex(n) => {
+            let ident = ctx.type_ident(n);
+            quote! { #ident }
+        }
+    }
+}
+
+/// Generates an expression decoding the `&xmltree::Element` bound to `e` as
+/// `ty`, or the type it is mapped to, evaluating to a `Result<_, savon::Error>`.
+fn gen_simple_from_element(
+    ty: &SimpleType,
+    mapping: Option<&Mapping>,
+    ctx: &Context,
+) -> TokenStream {
+    if let Some(mapping) = mapping {
+        let rust_type = &mapping.rust_type;
+        return match mapping.convert() {
+            Some(convert) => quote! {
+                e.get_text()
+                    .ok_or(savon::rpser::xml::Error::Empty)
+                    .map_err(savon::Error::from)
+                    .and_then(|s| #convert::from_text(&s))
+            },
+            None => quote! { <#rust_type as savon::gen::FromElement>::from_element(e) },
+        };
+    }
+
+    match ty {
+        // TODO: Properly parse this...
+        SimpleType::Base64Binary | SimpleType::String => quote! {
+            e.get_text().map(|s| s.to_string())
+                .ok_or(savon::rpser::xml::Error::Empty)
+                .map_err(savon::Error::from)
+        },
+        // literal elements have no `xsi:type`, so the text is parsed as is
+        SimpleType::Boolean => quote! {
+            e.get_text()
+                .ok_or(savon::rpser::xml::Error::Empty)
+                .map_err(savon::Error::from)
+                .map(|s| matches!(s.trim(), "true" | "1"))
+        },
+        SimpleType::Float => quote! {
+            e.get_text()
+                .ok_or(savon::rpser::xml::Error::Empty)
+                .map_err(savon::Error::from)
+                .and_then(|s| s.parse().map_err(savon::Error::from))
+        },
+        SimpleType::Int => quote! {
+            e.get_text()
+                .ok_or(savon::rpser::xml::Error::Empty)
+                .map_err(savon::Error::from)
+                .and_then(|s| s.trim().parse::<i64>()
+                          .map_err(|inner| savon::Error::from(savon::rpser::xml::Error::ParseIntError {
+                              name: e.name.clone(),
+                              inner,
+                          })))
+        },
+        SimpleType::DateTime => quote! {
+            e.get_text()
+                .ok_or(savon::rpser::xml::Error::Empty)
+                .map_err(savon::Error::from)
+                .and_then(|s| s.parse::<savon::internal::chrono::DateTime<savon::internal::chrono::offset::Utc>>()
+                          .map_err(|inner| savon::Error::from(savon::rpser::xml::Error::ParseDateTimeError {
+                              name: e.name.clone(),
+                              inner,
+                          })))
+        },
+        SimpleType::Complex(n) => {
+            let complex_type = ctx.type_ident(n);
+            quote! { #complex_type::from_element(e) }
+        }
+    }
+}
+
+/// How a field is represented, derived from its `minOccurs`, `maxOccurs` and
+/// `nillable` attributes, regardless of which of them were spelled out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Occurs {
+    /// Exactly one occurrence: `T`.
+    One,
+    /// Absent (`minOccurs="0"`) or nil: `Option<T>`.
+    Optional,
+    /// `maxOccurs` above 1 or unbounded: `Vec<T>`, `Vec<Option<T>>` when nillable.
+    Many,
+}
+
+impl Occurs {
+    fn of(attributes: &TypeAttribute) -> Self {
+        match attributes.max() {
+            Some(0 | 1) if attributes.min() == 0 || attributes.nillable => Occurs::Optional,
+            Some(0 | 1) => Occurs::One,
+            _ => Occurs::Many,
+        }
+    }
+}
+
+fn gen_field_type(
+    attributes: &TypeAttribute,
+    ty: &SimpleType,
+    mapping: Option<&Mapping>,
+    ctx: &Context,
+) -> TokenStream {
+    let ft = gen_simple(ty, mapping, ctx);
+
+    match Occurs::of(attributes) {
+        Occurs::One => ft,
+        Occurs::Optional => quote! { Option<#ft> },
+        Occurs::Many => {
+            let item = if attributes.nillable {
+                quote! { Option<#ft> }
+            } else {
+                ft
+            };
+
+            let (min, max) = (attributes.min(), attributes.max());
+            if bounded(attributes, ctx) {
+                let min = Literal::usize_unsuffixed(min as usize);
+                let max = match max {
+                    Some(max) => {
+                        let max = Literal::usize_unsuffixed(max as usize);
+                        quote! { #max }
+                    }
+                    None => quote! { { usize::MAX } },
+                };
+                quote! { savon::bounded::BoundedVec<#item, #min, #max> }
+            } else {
+                quote! { Vec<#item> }
+            }
+        }
+    }
+}
+
+/// Whether a repeated field is a `savon::bounded::BoundedVec`.
+fn bounded(attributes: &TypeAttribute, ctx: &Context) -> bool {
+    ctx.options.bounded_vecs && (attributes.min() > 0 || attributes.max().is_some())
+}
+
+/// Generates the `NamespaceMatch` a field's elements are decoded with.
+fn gen_namespace_match(namespace: Option<&str>, ctx: &Context) -> TokenStream {
+    match (ctx.options.lenient_namespaces, namespace) {
+        (true, _) => quote! { savon::rpser::xml::NamespaceMatch::Any },
+        (false, Some(ns)) => quote! { savon::rpser::xml::NamespaceMatch::Qualified(#ns) },
+        (false, None) => quote! { savon::rpser::xml::NamespaceMatch::Unqualified },
+    }
+}
+
+/// Members of a field's substitution group whose type differs from the
+/// field's, with the namespace and name of the member element and of its type.
+///
+/// Members of the same type are read and written like the field itself.
+fn substitutes<'a>(
+    attributes: &'a TypeAttribute,
+    field_type: &SimpleType,
+    ctx: &Context,
+) -> Vec<(&'a QualifiedTypename, &'a QualifiedTypename)> {
+    let SimpleType::Complex(head) = field_type else {
+        return vec![];
+    };
+    let Some(variants) = ctx.variants.get(head) else {
+        return vec![];
+    };
+
+    attributes
+        .substitutions
+        .iter()
+        .filter_map(|(element, member_type)| match member_type {
+            SimpleType::Complex(t) if t != head && variants.contains(t) => Some((element, t)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generates the `{Type}Kind` enum over the concrete types which may occur in
+/// place of `name`, telling them apart by their `xsi:type`.
+fn gen_variants(
+    name: &QualifiedTypename,
+    variants: &[QualifiedTypename],
+    ctx: &Context,
+) -> TokenStream {
+    let kind_name = ctx.type_ident(name);
+    let base_namespace = name.namespace();
+    let base_name = name.name();
+
+    let mut scope = Scope::default();
+    let idents = variants
+        .iter()
+        .map(|v| scope.ident(&string::to_pascal(v.name())))
+        .collect::<Vec<_>>();
+    let types = variants
+        .iter()
+        .map(|v| ctx.concrete_ident(v))
+        .collect::<Vec<_>>();
+    let first = &idents[0];
+    let namespaces = variants.iter().map(|v| v.namespace()).collect::<Vec<_>>();
+    let names = variants.iter().map(|v| v.name()).collect::<Vec<_>>();
+    let patterns = variants.iter().map(|v| {
+        let name = v.name();
+        if ctx.options.lenient_namespaces {
+            quote! { (_, #name) }
+        } else {
+            let namespace = v.namespace();
+            quote! { (#namespace, #name) }
+        }
+    });
+
+    let doc = gen_doc(&[
+        ctx.documentation.get(name).copied(),
+        Some(&format!(
+            "Qualified type {name} or any of its derived types."
+        )),
+    ]);
+    let variant_docs = variants
+        .iter()
+        .map(|v| gen_doc(&[ctx.documentation.get(v).copied()]));
+    let (vis, derives) = (&ctx.vis, &ctx.derives);
+    let serde = ctx.serde_container(quote! {});
+    let renames = names
+        .iter()
+        .map(|name| ctx.serde_attr(quote! { rename = #name }));
+
+    quote! {
+        #doc
+        #[derive(Clone, Debug, #derives)]
+        #serde
+        #vis enum #kind_name {
+            #(#variant_docs #renames #idents(#types),)*
+        }
+
+        impl Default for #kind_name {
+            fn default() -> Self {
+                #kind_name::#first(Default::default())
+            }
+        }
+
+        impl #kind_name {
+            /// Namespace and name of the type of the value, written as its `xsi:type`.
+            pub fn type_name(&self) -> (&'static str, &'static str) {
+                match self {
+                    #(#kind_name::#idents(_) => (#namespaces, #names),)*
+                }
+            }
+
+            /// Decodes `element` as the type named by its `xsi:type`, or as the
+            /// given type without one.
+            pub fn from_element_as(element: &xmltree::Element, namespace: &str, name: &str) -> Result<Self, savon::Error> {
+                let xsi_type = element.get_xsi_type();
+                let (namespace, name) = match &xsi_type {
+                    Some((namespace, name)) => (namespace.as_str(), name.as_str()),
+                    None => (namespace, name),
+                };
+
+                match (namespace, name) {
+                    #(#patterns => <#types as savon::gen::FromElement>::from_element(element).map(#kind_name::#idents),)*
+                    _ => Err(savon::rpser::xml::Error::ExpectedElementWithType {
+                        name: element.name.clone(),
+                        expected_type: #base_name.to_string(),
+                        given: Some(name.to_string()),
+                    }.into()),
+                }
+            }
+        }
+
+        impl savon::gen::ToElements for #kind_name {
+            fn to_elements(&self) -> Vec<xmltree::Element> {
+                match self {
+                    #(#kind_name::#idents(v) => v.to_elements(),)*
+                }
+            }
+
+            fn to_nodes(&self) -> Vec<xmltree::XMLNode> {
+                match self {
+                    #(#kind_name::#idents(v) => v.to_nodes(),)*
+                }
+            }
+
+            fn to_attributes(&self) -> Vec<(String, String)> {
+                let mut attributes = match self {
+                    #(#kind_name::#idents(v) => v.to_attributes(),)*
+                };
+                // the namespace is bound to a prefix when serializing the message
+                let (namespace, name) = self.type_name();
+                attributes.push(("xsi:type".to_string(), format!("{{{namespace}}}{name}")));
+                attributes
+            }
+        }
+
+        impl savon::gen::FromElement for #kind_name {
+            fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                #kind_name::from_element_as(element, #base_namespace, #base_name)
+            }
+        }
+    }
+}
+
+/// Generates an expression serializing the value bound to `v` as the element
+/// created by `node`.
+fn gen_simple_to_element(
+    ty: &SimpleType,
+    mapping: Option<&Mapping>,
+    node: &TokenStream,
+) -> TokenStream {
+    if let Some(convert) = mapping.and_then(Mapping::convert) {
+        return quote! { #node.with_text(#convert::to_text(v)) };
+    }
+
+    match ty {
+        _ if mapping.is_some() => {
+            quote! { #node.with_attrs(v.to_attributes()).with_nodes(v.to_nodes()) }
+        }
+        SimpleType::Complex(_) => {
+            quote! { #node.with_attrs(v.to_attributes()).with_nodes(v.to_nodes()) }
+        }
+        SimpleType::DateTime => quote! { #node.with_text(v.to_rfc3339()) },
+        _ => quote! { #node.with_text(v.to_string()) },
+    }
+}
+
+/// Generates an expression converting the `&str` bound to `s` to `ty`,
+/// evaluating to a `Result<_, savon::Error>`. Named simple types are strings,
+/// unless mapped to a type read as text.
+fn gen_simple_from_str(ty: &SimpleType, mapping: Option<&Mapping>, name: &str) -> TokenStream {
+    if let Some(convert) = mapping.and_then(Mapping::convert) {
+        return quote! { #convert::from_text(s) };
+    }
+
+    match ty {
+        SimpleType::Boolean => quote! { Ok::<_, savon::Error>(matches!(s.trim(), "true" | "1")) },
+        SimpleType::Float => quote! { s.parse::<f64>().map_err(savon::Error::from) },
+        SimpleType::Int => quote! {
+            s.parse::<i64>()
+                .map_err(|inner| savon::Error::from(savon::rpser::xml::Error::ParseIntError {
+                    name: #name.to_string(),
+                    inner,
+                }))
+        },
+        SimpleType::DateTime => quote! {
+            s.parse::<savon::internal::chrono::DateTime<savon::internal::chrono::offset::Utc>>()
+                .map_err(|inner| savon::Error::from(savon::rpser::xml::Error::ParseDateTimeError {
+                    name: #name.to_string(),
+                    inner,
+                }))
+        },
+        SimpleType::Base64Binary | SimpleType::String | SimpleType::Complex(_) => {
+            quote! { Ok::<_, savon::Error>(s.to_string()) }
+        }
+    }
+}
+
+/// Generates an expression converting the `&str` bound to `s` to a value of
+/// a named simple type, list or union, parsing named simple types with their
+/// `FromStr` implementation.
+fn gen_value_from_str(ty: &SimpleType, name: &str, ctx: &Context) -> TokenStream {
+    match ty {
+        SimpleType::Complex(n) => {
+            let ident = ctx.type_ident(n);
+            quote! { s.parse::<#ident>() }
+        }
+        _ => gen_simple_from_str(ty, None, name),
+    }
+}
+
+/// Name of the variant of a union holding values of the member type `ty`.
+fn union_variant(ty: &SimpleType) -> Ident {
+    let name = match ty {
+        SimpleType::Base64Binary => "Base64Binary".to_string(),
+        SimpleType::Boolean => "Boolean".to_string(),
+        SimpleType::String => "String".to_string(),
+        SimpleType::Float => "Float".to_string(),
+        SimpleType::Int => "Int".to_string(),
+        SimpleType::DateTime => "DateTime".to_string(),
+        SimpleType::Complex(n) => string::to_pascal(n.name()),
+    };
+    ident::ident(&name)
+}
+
+/// Generates the `ToElements` and `FromElement` implementations of a type
+/// written as the text of its element, through `Display` and `FromStr`.
+fn gen_text_impl(type_name: &Ident) -> TokenStream {
+    quote! {
+        impl savon::gen::ToElements for #type_name {
+            fn to_elements(&self) -> Vec<xmltree::Element> {
+                vec![]
+            }
+
+            fn to_nodes(&self) -> Vec<xmltree::XMLNode> {
+                vec![xmltree::XMLNode::Text(self.to_string())]
+            }
+        }
+
+        impl savon::gen::FromElement for #type_name {
+            fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                element.get_text().unwrap_or_default().parse()
+            }
+        }
+    }
+}
+
+/// Generates an expression formatting the value bound to `v` with `{}`.
+fn gen_simple_display(ty: &SimpleType) -> TokenStream {
+    match ty {
+        SimpleType::DateTime => quote! { v.to_rfc3339() },
+        _ => quote! { v },
+    }
+}
+
+/// Generates an expression converting the value bound to `v` to a `String`.
+fn gen_simple_to_string(ty: &SimpleType, mapping: Option<&Mapping>) -> TokenStream {
+    if let Some(convert) = mapping.and_then(Mapping::convert) {
+        return quote! { #convert::to_text(v) };
+    }
+
+    match ty {
+        SimpleType::DateTime => quote! { v.to_rfc3339() },
+        _ => quote! { v.to_string() },
+    }
+}
+
+/// Only value of a field, if fixed by the schema. Single elements and
+/// attributes with a fixed value are written without a struct field.
+fn fixed_value(attributes: &TypeAttribute) -> Option<&str> {
+    attributes
+        .fixed
+        .as_deref()
+        .filter(|_| Occurs::of(attributes) != Occurs::Many)
+}
+
+/// Generates the schema default of a single, non nillable, element or
+/// attribute of a built-in type as an expression of the field type. Mapped
+/// types have no schema default.
+fn gen_default(
+    attributes: &TypeAttribute,
+    ty: &SimpleType,
+    mapping: Option<&Mapping>,
+) -> Option<TokenStream> {
+    let value = attributes.default.as_deref()?;
+    if attributes.nillable || Occurs::of(attributes) == Occurs::Many {
+        return None;
+    }
+    if mapping.is_some() {
+        warn!("ignoring default value {value:?} of a mapped type");
+        return None;
+    }
+
+    let default = match ty {
+        SimpleType::Base64Binary | SimpleType::String => Some(quote! { #value.to_string() }),
+        SimpleType::Boolean => match value.trim() {
+            "true" | "1" => Some(quote! { true }),
+            "false" | "0" => Some(quote! { false }),
+            _ => None,
+        },
+        SimpleType::Int => value.trim().parse::<i64>().ok().map(|n| {
+            let abs = Literal::u64_unsuffixed(n.unsigned_abs());
+            if n < 0 {
+                quote! { -#abs }
+            } else {
+                quote! { #abs }
+            }
+        }),
+        SimpleType::Float => value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|f| f.is_finite())
+            .map(|f| {
+                let abs = Literal::f64_unsuffixed(f.abs());
+                if f.is_sign_negative() {
+                    quote! { -#abs }
+                } else {
+                    quote! { #abs }
+                }
+            }),
+        SimpleType::DateTime => value
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .ok()
+            .map(|_| quote! { #value.parse().expect("schema default is a valid dateTime") }),
+        SimpleType::Complex(_) => None,
+    };
+
+    if default.is_none() {
+        warn!("unsupported default value {value:?} for {ty:?}");
+    }
+    default
+}
+
+/// Rust names of the fields of a complex type, unique within it: the fields
+/// of wildcards and mixed content come first, then the elements, then the
+/// attributes.
+struct FieldNames<'a> {
+    elements: BTreeMap<&'a str, Ident>,
+    attributes: BTreeMap<&'a str, Ident>,
+}
+
+impl<'a> FieldNames<'a> {
+    fn new(type_name: &QualifiedTypename, c: &'a ComplexType, options: &GenOptions) -> Self {
+        let reserved = [
+            (c.any && !c.mixed).then_some("any"),
+            c.any_attribute.then_some("any_attributes"),
+            c.mixed.then_some("content"),
+        ];
+        let mut scope = Scope::new(&reserved.into_iter().flatten().collect::<Vec<_>>());
+
+        let mut names = |fields: &'a [(String, (TypeAttribute, SimpleType))]| {
+            let mut names = BTreeMap::new();
+            for (name, _) in fields.iter() {
+                names.entry(name.as_str()).or_insert_with(|| {
+                    let key = format!("{}.{name}", type_name.name());
+                    scope.ident(&options.renamed(&key, || string::to_snake(name)))
+                });
+            }
+            names
+        };
+        // mixed content elements are variants of the segments, not fields
+        let elements = if c.mixed {
+            BTreeMap::new()
+        } else {
+            names(&c.fields)
+        };
+        let attributes = names(&c.attributes);
+
+        FieldNames {
+            elements,
+            attributes,
+        }
+    }
+
+    fn element(&self, name: &str) -> Ident {
+        self.elements[name].clone()
+    }
+
+    fn attribute(&self, name: &str) -> Ident {
+        self.attributes[name].clone()
+    }
+
+    /// Serde name of the attribute `name`, prefixed with `@` if an element
+    /// has the same name.
+    fn attribute_key(&self, name: &str) -> String {
+        if self.elements.contains_key(name) {
+            format!("@{name}")
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+/// Generated code for the declared attributes and the `<anyAttribute/>`
+/// wildcard of a complex type.
+struct AttributesImpl {
+    /// Struct field declarations.
+    fields: Vec<TokenStream>,
+    /// `ToElements::to_attributes` implementation, if the type has any.
+    to_attributes: Option<TokenStream>,
+    /// Struct literal entries decoding the fields from `element`.
+    from_element: Vec<TokenStream>,
+    /// Statements checking fixed values of `element`.
+    checks: Vec<TokenStream>,
+    /// Struct literal entries of the `Default` implementation.
+    defaults: Vec<TokenStream>,
+    /// Whether a field has a schema default.
+    has_defaults: bool,
+    /// Fields set by the builder.
+    builder: Vec<BuilderField>,
+}
+
+fn gen_attributes(name: &QualifiedTypename, c: &ComplexType, ctx: &Context) -> AttributesImpl {
+    let vis = &ctx.vis;
+    let field_names = FieldNames::new(name, c, ctx.options);
+    let mut attrs = AttributesImpl {
+        fields: vec![],
+        to_attributes: None,
+        from_element: vec![],
+        checks: vec![],
+        defaults: vec![],
+        has_defaults: false,
+        builder: vec![],
+    };
+    let mut items = vec![];
+
+    for (attribute_name, (attributes, attribute_type)) in c.attributes.iter() {
+        // named simple types are written and read as strings
+        let ty = match attribute_type {
+            SimpleType::Complex(_) => SimpleType::String,
+            t => t.clone(),
+        };
+
+        if let Some(fixed) = fixed_value(attributes) {
+            items.push(quote! { Some((#attribute_name.to_string(), #fixed.to_string())) });
+            attrs
+                .checks
+                .push(quote! { element.check_fixed_attribute(#attribute_name, #fixed)?; });
+            continue;
+        }
+
+        let fname = field_names.attribute(attribute_name);
+        // attribute values are text, not element content
+        let mapping = ctx
+            .mapping(name, attribute_name, attribute_type)
+            .filter(|mapping| {
+                if mapping.converter.is_none() {
+                    warn!("ignoring element mapping of attribute {attribute_name} of {name}");
+                }
+                mapping.converter.is_some()
+            });
+        let parse = gen_simple_from_str(&ty, mapping, attribute_name);
+        let to_string = gen_simple_to_string(&ty, mapping);
+        let rust_type = match (mapping, &ty) {
+            (Some(mapping), _) => mapping.rust_type.clone(),
+            (None, SimpleType::Boolean) => quote! { bool },
+            (None, SimpleType::Float) => quote! { f64 },
+            (None, SimpleType::Int) => quote! { i64 },
+            (None, SimpleType::DateTime) => {
+                quote! { savon::internal::chrono::DateTime<savon::internal::chrono::offset::Utc> }
+            }
+            (None, _) => quote! { String },
+        };
+
+        let default = gen_default(attributes, &ty, mapping);
+        let optional = default.is_none() && Occurs::of(attributes) != Occurs::One;
+        attrs.builder.push(BuilderField {
+            name: fname.clone(),
+            xml_name: attribute_name.clone(),
+            kind: match &default {
+                Some(default) => BuilderKind::Defaulted(rust_type.clone(), default.clone()),
+                None if optional => BuilderKind::Optional(rust_type.clone()),
+                None => BuilderKind::Required(rust_type.clone()),
+            },
+        });
+        let (field_type, from_element) = match &default {
+            Some(default) => (
+                rust_type,
+                quote! {
+                    match element.attributes.get(#attribute_name) {
+                        Some(s) => #parse?,
+                        None => #default,
+                    }
+                },
+            ),
+            None if !optional => (
+                rust_type,
+                quote! {
+                    {
+                        let s = element.get_attribute(#attribute_name)?;
+                        #parse?
+                    }
+                },
+            ),
+            None => (
+                quote! { Option<#rust_type> },
+                quote! { element.attributes.get(#attribute_name).map(|s| #parse).transpose()? },
+            ),
+        };
+
+        if optional {
+            items.push(quote! {
+                self.#fname.as_ref().map(|v| (#attribute_name.to_string(), #to_string))
+            });
+        } else {
+            items.push(quote! {
+                Some((#attribute_name.to_string(), {
+                    let v = &self.#fname;
+                    #to_string
+                }))
+            });
+        }
+
+        let doc = gen_doc(&[
+            attributes.documentation.as_deref(),
+            Some(&format!("Attribute `{attribute_name}`.")),
+        ]);
+        let key = field_names.attribute_key(attribute_name);
+        let rename = ctx.serde_attr(quote! { rename = #key });
+        attrs.fields.push(quote! {
+            #doc
+            #rename
+            #vis #fname: #field_type,
+        });
+        attrs.from_element.push(quote! { #fname: #from_element, });
+        attrs.has_defaults |= default.is_some();
+        let default = default.unwrap_or_else(|| quote! { Default::default() });
+        attrs.defaults.push(quote! { #fname: #default, });
+    }
+
+    let any_attributes = c.any_attribute.then(|| {
+        quote! { self.any_attributes.iter().map(|(k, v)| (k.clone(), v.clone())) }
+    });
+    if c.any_attribute {
+        let declared = c.attributes.iter().map(|(name, _)| name);
+        attrs.fields.push(quote! {
+            /// Attributes matching the `<anyAttribute/>` wildcard.
+            #vis any_attributes: std::collections::BTreeMap<String, String>,
+        });
+        attrs.from_element.push(quote! {
+            any_attributes: element.get_undeclared_attributes(&[#(#declared),*]),
+        });
+        attrs
+            .defaults
+            .push(quote! { any_attributes: Default::default(), });
+    }
+
+    attrs.to_attributes = match (items.is_empty(), any_attributes) {
+        (true, None) => None,
+        (true, Some(any_attributes)) => Some(quote! {
+            fn to_attributes(&self) -> Vec<(String, String)> {
+                #any_attributes.collect()
+            }
+        }),
+        (false, any_attributes) => {
+            let chain = any_attributes.map(|any_attributes| quote! { .chain(#any_attributes) });
+            Some(quote! {
+                fn to_attributes(&self) -> Vec<(String, String)> {
+                    [#(#items),*].into_iter().flatten()#chain.collect()
+                }
+            })
+        }
+    };
+
+    attrs
+}
+
+/// A field of a generated struct, as set by its builder.
+struct BuilderField {
+    name: Ident,
+    xml_name: String,
+    kind: BuilderKind,
+}
+
+enum BuilderKind {
+    /// A required field of this type, which must be set before building.
+    Required(TokenStream),
+    /// A field of this type, set to this default unless given.
+    Defaulted(TokenStream, TokenStream),
+    /// An optional field of this type.
+    Optional(TokenStream),
+    /// A repeated field of items of this type, with a minimum number of
+    /// occurrences, collected into a `BoundedVec` if bounded.
+    Repeated {
+        item: TokenStream,
+        min: u32,
+        bounded: bool,
+    },
+}
+
+/// Generates the `{Type}Builder` of a complex type, with `with_` setters of
+/// single fields, `push_` methods adding the items of repeated ones, and a
+/// `build` method checking that the required ones are set.
+fn gen_builder(
+    name: &QualifiedTypename,
+    c: &ComplexType,
+    elements: &[BuilderField],
+    attributes: &[BuilderField],
+    ctx: &Context,
+) -> TokenStream {
+    let builder_name = &ctx.builder_names[name];
+    let type_name = ctx.concrete_ident(name);
+    let type_str = name.name();
+    let vis = &ctx.vis;
+
+    let mut scope = Scope::new(&["build"]);
+    let mut method = |prefix: &str, field: &Ident| {
+        let field = field.to_string();
+        let field = field.trim_start_matches("r#").trim_matches('_');
+        scope.ident(&format!("{prefix}_{field}"))
+    };
+
+    let mut builder_fields = vec![];
+    let mut initializers = vec![];
+    let mut methods = vec![];
+    let mut build = vec![];
+    for BuilderField {
+        name: fname,
+        xml_name,
+        kind,
+    } in elements.iter().chain(attributes)
+    {
+        let doc = format!(" Sets `{xml_name}`.");
+        let (ty, initializer) = match kind {
+            BuilderKind::Required(ty) => {
+                let with = method("with", fname);
+                methods.push(quote! {
+                    #[doc = #doc]
+                    #vis fn #with(mut self, value: impl Into<#ty>) -> Self {
+                        self.#fname = Some(value.into());
+                        self
+                    }
+                });
+                build.push(quote! {
+                    #fname: self.#fname.ok_or(savon::gen::MissingField {
+                        type_name: #type_str,
+                        field: #xml_name,
+                    })?,
+                });
+                (quote! { Option<#ty> }, quote! { None })
+            }
+            BuilderKind::Defaulted(ty, default) => {
+                let with = method("with", fname);
+                methods.push(quote! {
+                    #[doc = #doc]
+                    #vis fn #with(mut self, value: impl Into<#ty>) -> Self {
+                        self.#fname = value.into();
+                        self
+                    }
+                });
+                build.push(quote! { #fname: self.#fname, });
+                (ty.clone(), default.clone())
+            }
+            BuilderKind::Optional(ty) => {
+                let with = method("with", fname);
+                methods.push(quote! {
+                    #[doc = #doc]
+                    #vis fn #with(mut self, value: impl Into<#ty>) -> Self {
+                        self.#fname = Some(value.into());
+                        self
+                    }
+                });
+                build.push(quote! { #fname: self.#fname, });
+                (quote! { Option<#ty> }, quote! { None })
+            }
+            BuilderKind::Repeated { item, min, bounded } => {
+                let push = method("push", fname);
+                let doc = format!(" Adds an occurrence of `{xml_name}`.");
+                methods.push(quote! {
+                    #[doc = #doc]
+                    #vis fn #push(mut self, item: impl Into<#item>) -> Self {
+                        self.#fname.push(item.into());
+                        self
+                    }
+                });
+                let check = (*min > 0).then(|| {
+                    quote! {
+                        if v.is_empty() {
+                            return Err(savon::gen::MissingField {
+                                type_name: #type_str,
+                                field: #xml_name,
+                            }
+                            .into());
+                        }
+                    }
+                });
+                let collect = if *bounded {
+                    quote! { savon::bounded::BoundedVec::try_from(v)? }
+                } else {
+                    quote! { v }
+                };
+                build.push(if check.is_none() && !*bounded {
+                    quote! { #fname: self.#fname, }
+                } else {
+                    quote! {
+                        #fname: {
+                            let v = self.#fname;
+                            #check
+                            #collect
+                        },
+                    }
+                });
+                (quote! { Vec<#item> }, quote! { vec![] })
+            }
+        };
+        builder_fields.push(quote! { #fname: #ty, });
+        initializers.push(quote! { #fname: #initializer, });
+    }
+
+    if c.any {
+        let push = method("push", &format_ident!("any"));
+        methods.push(quote! {
+            /// Adds an element matching the `<any/>` wildcard.
+            #vis fn #push(mut self, element: xmltree::Element) -> Self {
+                self.any.push(element);
+                self
+            }
+        });
+        builder_fields.push(quote! { any: Vec<xmltree::Element>, });
+        initializers.push(quote! { any: vec![], });
+        build.push(quote! { any: self.any, });
+    }
+    if c.any_attribute {
+        let with = method("with", &format_ident!("any_attribute"));
+        methods.push(quote! {
+            /// Sets an attribute matching the `<anyAttribute/>` wildcard.
+            #vis fn #with(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+                self.any_attributes.insert(name.into(), value.into());
+                self
+            }
+        });
+        builder_fields.push(quote! { any_attributes: std::collections::BTreeMap<String, String>, });
+        initializers.push(quote! { any_attributes: Default::default(), });
+        build.push(quote! { any_attributes: self.any_attributes, });
+    }
+
+    let doc = format!(" Builder of [`{type_name}`], checking that its required fields are set.");
+    quote! {
+        #[doc = #doc]
+        #[derive(Clone, Debug)]
+        #vis struct #builder_name {
+            #(#builder_fields)*
+        }
+
+        impl #type_name {
+            /// A builder of the value, with no field set.
+            #vis fn builder() -> #builder_name {
+                #builder_name {
+                    #(#initializers)*
+                }
+            }
+        }
+
+        impl #builder_name {
+            #(#methods)*
+
+            /// Builds the value, failing if a required field is not set.
+            #vis fn build(self) -> Result<#type_name, savon::Error> {
+                Ok(#type_name {
+                    #(#build)*
+                })
+            }
+        }
+    }
+}
+
+/// Generates a type with mixed content, a list of text and element segments
+/// in document order. The occurrences of the elements are not checked.
+fn gen_mixed(name: &QualifiedTypename, c: &ComplexType, ctx: &Context) -> TokenStream {
+    let type_name = ctx.concrete_ident(name);
+    let segment_name = ctx.segment_ident(name);
+    let (vis, derives) = (&ctx.vis, &ctx.derives);
+
+    let mut scope = Scope::new(&["Text", "Other"]);
+    let mut variant_names = BTreeMap::new();
+    for (field_name, _) in c.fields.iter() {
+        variant_names.entry(field_name.as_str()).or_insert_with(|| {
+            let key = format!("{}.{field_name}", name.name());
+            scope.ident(&ctx.options.renamed(&key, || string::to_pascal(field_name)))
+        });
+    }
+
+    let variants = c
+        .fields
+        .iter()
+        .map(|(field_name, (attributes, field_type))| {
+            let variant = &variant_names[field_name.as_str()];
+            let mapping = ctx.mapping(name, field_name, field_type);
+            let ft = gen_simple(field_type, mapping, ctx);
+            let doc = gen_doc(&[attributes.documentation.as_deref()]);
+            let rename = ctx.serde_attr(quote! { rename = #field_name });
+            quote! { #doc #rename #variant(#ft), }
+        })
+        .chain(c.any.then(|| {
+            let skip = ctx.serde_attr(quote! { skip });
+            quote! {
+                /// Element matching the `<any/>` wildcard.
+                #skip
+                Other(xmltree::Element),
+            }
+        }));
+
+    let serialize = c
+        .fields
+        .iter()
+        .map(|(field_name, (attributes, field_type))| {
+            let variant = &variant_names[field_name.as_str()];
+            let node = match attributes.namespace.as_ref() {
+                Some(ns) => quote! { xmltree::Element::node(#field_name).with_namespace(#ns) },
+                None => quote! { xmltree::Element::node(#field_name) },
+            };
+            let mapping = ctx.mapping(name, field_name, field_type);
+            let item = gen_simple_to_element(field_type, mapping, &node);
+            quote! { #segment_name::#variant(v) => xmltree::XMLNode::Element(#item), }
+        })
+        .chain(c.any.then(|| {
+            quote! { #segment_name::Other(v) => xmltree::XMLNode::Element(v.clone()), }
+        }));
+
+    let deserialize = c
+        .fields
+        .iter()
+        .map(|(field_name, (attributes, field_type))| {
+            let variant = &variant_names[field_name.as_str()];
+            let namespace = gen_namespace_match(attributes.namespace.as_deref(), ctx);
+            let mapping = ctx.mapping(name, field_name, field_type);
+            let parse = gen_simple_from_element(field_type, mapping, ctx);
+            quote! {
+                xmltree::XMLNode::Element(e) if #namespace.matches(e, #field_name) => {
+                    content.push(#segment_name::#variant(#parse?))
+                }
+            }
+        })
+        .chain(Some(if c.any {
+            quote! { xmltree::XMLNode::Element(e) => content.push(#segment_name::Other(e.clone())), }
+        } else {
+            quote! { xmltree::XMLNode::Element(_) => {} }
+        }));
+
+    let AttributesImpl {
+        fields: attribute_fields,
+        to_attributes,
+        from_element: attributes_from_element,
+        checks,
+        defaults,
+        has_defaults,
+        ..
+    } = gen_attributes(name, c, ctx);
+    let (derive_default, default_impl) = if has_defaults {
+        (
+            None,
+            Some(quote! {
+                impl Default for #type_name {
+                    fn default() -> Self {
+                        #type_name {
+                            content: Default::default(),
+                            #(#defaults)*
+                        }
+                    }
+                }
+            }),
+        )
+    } else {
+        (Some(quote! { Default, }), None)
+    };
+
+    let doc = ctx.type_doc(name);
+    let segment_docstr = format!(" A segment of the mixed content of [`{type_name}`].");
+    let serde = ctx.serde_container(quote! { default });
+    let segment_serde = ctx.serde_container(quote! {});
+    let derived = ctx
+        .variants
+        .get(name)
+        .map(|variants| gen_variants(name, variants, ctx));
+
+    quote! {
+        #doc
+        #[derive(Clone, Debug, #derive_default #derives)]
+        #serde
+        #vis struct #type_name {
+            /// Text and elements in document order.
+            #vis content: Vec<#segment_name>,
+            #(#attribute_fields)*
+        }
+
+        #default_impl
+
+        #[doc = #segment_docstr]
+        #[derive(Clone, Debug, #derives)]
+        #segment_serde
+        #vis enum #segment_name {
+            Text(String),
+            #(#variants)*
+        }
+
+        impl savon::gen::ToElements for #type_name {
+            fn to_elements(&self) -> Vec<xmltree::Element> {
+                self.to_nodes()
+                    .into_iter()
+                    .filter_map(|node| match node {
+                        xmltree::XMLNode::Element(e) => Some(e),
+                        _ => None,
+                    })
+                    .collect()
+            }
+
+            fn to_nodes(&self) -> Vec<xmltree::XMLNode> {
+                self.content
+                    .iter()
+                    .map(|segment| match segment {
+                        #segment_name::Text(v) => xmltree::XMLNode::Text(v.clone()),
+                        #(#serialize)*
+                    })
+                    .collect()
+            }
+
+            #to_attributes
+        }
+
+        impl savon::gen::FromElement for #type_name {
+            fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                #(#checks)*
+
+                let mut content = vec![];
+                for node in element.children.iter() {
+                    match node {
+                        xmltree::XMLNode::Text(text) => content.push(#segment_name::Text(text.clone())),
+                        #(#deserialize)*
+                        _ => {}
+                    }
+                }
+
+                Ok(#type_name {
+                    content,
+                    #(#attributes_from_element)*
+                })
+            }
+        }
+
+        #derived
+    }
+}
+
+fn gen_type(name: &QualifiedTypename, t: &Type, ctx: &Context) -> TokenStream {
+    let type_name = ctx.concrete_ident(name);
+    let (vis, derives) = (&ctx.vis, &ctx.derives);
+    // unions are written as their value, telling the members apart when reading
+    let serde = match t {
+        Type::Union(_) => ctx.serde_container(quote! { untagged }),
+        _ => ctx.serde_container(quote! {}),
+    };
+
+    match t {
+        Type::Complex(c) if c.mixed => gen_mixed(name, c, ctx),
+        Type::Complex(c) => {
+            let attrs = gen_attributes(name, c, ctx);
+            let field_names = FieldNames::new(name, c, ctx.options);
+
+            let fields = c
+                .fields
+                .iter()
+                .filter(|(_, (attributes, _))| fixed_value(attributes).is_none())
+                .map(|(field_name, (attributes, field_type))| {
+                    let tgt = if let SimpleType::Complex(n) = field_type {
+                        Some(n.clone())
+                    } else {
+                        None
+                    };
+
+                    let fname = field_names.element(field_name);
+                    let mapping = ctx.mapping(name, field_name, field_type);
+                    // absent elements with a default are given their default value
+                    let ft = if gen_default(attributes, field_type, mapping).is_some() {
+                        gen_simple(field_type, mapping, ctx)
+                    } else {
+                        gen_field_type(attributes, field_type, mapping, ctx)
+                    };
+
+                    let doc = gen_doc(&[
+                        attributes.documentation.as_deref(),
+                        tgt.map(|tgt| format!("Qualified type: {tgt}")).as_deref(),
+                    ]);
+                    let rename = ctx.serde_attr(quote! { rename = #field_name });
+
+                    quote! {
+                        #doc
+                        #rename
+                        #vis #fname: #ft,
+                    }
+                })
+                .collect::<Vec<_>>();
+            let any_field = c.any.then(|| {
+                let skip = ctx.serde_attr(quote! { skip });
+                quote! {
+                    /// Elements matching the `<any/>` wildcard.
+                    #skip
+                    #vis any: Vec<xmltree::Element>,
+                }
+            });
+            let attribute_fields = &attrs.fields;
+
+            let fields_serialize_impl = c
+                .fields
+                .iter()
+                .map(|(field_name, (attributes, field_type))| {
+                    let fname = field_names.element(field_name);
+                    let ftype = Literal::string(field_name);
+                    let prefix = match attributes.namespace.as_ref() {
+                        Some(ns) => quote! { xmltree::Element::node(#ftype).with_namespace(#ns) },
+                        None => quote! { xmltree::Element::node(#ftype) },
+                    };
+                    let mapping = ctx.mapping(name, field_name, field_type);
+                    // a derived value is written as the member of the
+                    // substitution group of its type, if any
+                    let substitutes = match mapping {
+                        Some(_) => vec![],
+                        None => substitutes(attributes, field_type, ctx),
+                    };
+                    let node = if substitutes.is_empty() {
+                        prefix.clone()
+                    } else {
+                        let arms = substitutes.iter().map(|(element, t)| {
+                            let (element_namespace, element_name) = (element.namespace(), element.name());
+                            let (type_namespace, type_name) = (t.namespace(), t.name());
+                            quote! {
+                                (#type_namespace, #type_name) => xmltree::Element::node(#element_name).with_namespace(#element_namespace),
+                            }
+                        });
+                        quote! {
+                            match v.type_name() {
+                                #(#arms)*
+                                _ => #prefix,
+                            }
+                        }
+                    };
+                    let item = gen_simple_to_element(field_type, mapping, &node);
+                    let nil = quote! { #prefix.with_attr("xsi:nil", "true") };
+
+                    if let Some(fixed) = fixed_value(attributes) {
+                        return quote! { vec![#prefix.with_text(#fixed)] };
+                    }
+
+                    match Occurs::of(attributes) {
+                        _ if gen_default(attributes, field_type, mapping).is_some() => quote! {
+                            {
+                                let v = &self.#fname;
+                                vec![#item]
+                            }
+                        },
+                        Occurs::One => quote! {
+                            {
+                                let v = &self.#fname;
+                                vec![#item]
+                            }
+                        },
+                        // an absent element cannot be told apart from a nil one
+                        // unless the element is required
+                        Occurs::Optional if attributes.nillable && attributes.min() > 0 => quote! {
+                            match &self.#fname {
+                                Some(v) => vec![#item],
+                                None => vec![#nil],
+                            }
+                        },
+                        Occurs::Optional => quote! {
+                            match &self.#fname {
+                                Some(v) => vec![#item],
+                                None => vec![],
+                            }
+                        },
+                        Occurs::Many if attributes.nillable => quote! {
+                            self.#fname.iter().map(|i| match i {
+                                Some(v) => #item,
+                                None => #nil,
+                            }).collect::<Vec<_>>()
+                        },
+                        Occurs::Many => quote! {
+                            self.#fname.iter().map(|v| #item).collect::<Vec<_>>()
+                        },
+                    }
+                })
+                // wildcard elements are written after the declared ones
+                .chain(c.any.then(|| quote! { self.any.clone() }))
+                .collect::<Vec<_>>();
+
+            let attributes_serialize_impl = &attrs.to_attributes;
+
+            let serialize_impl = if fields_serialize_impl.is_empty() {
+                quote! {
+                    impl savon::gen::ToElements for #type_name {
+                        fn to_elements(&self) -> Vec<xmltree::Element> {
+                            vec![]
+                        }
+
+                        #attributes_serialize_impl
+                    }
+                }
+            } else {
+                quote! {
+                    impl savon::gen::ToElements for #type_name {
+                        fn to_elements(&self) -> Vec<xmltree::Element> {
+                            vec![#(#fields_serialize_impl),*].drain(..).flatten().collect()
+                        }
+
+                        #attributes_serialize_impl
+                    }
+                }
+            };
+
+            let fixed_checks = c
+                .fields
+                .iter()
+                .filter_map(|(field_name, (attributes, _))| {
+                    let fixed = fixed_value(attributes)?;
+                    let namespace = gen_namespace_match(attributes.namespace.as_deref(), ctx);
+                    let (min, max) = (attributes.min(), attributes.max().unwrap_or(1));
+
+                    Some(quote! {
+                        for e in element.get_occurrences(#namespace, #field_name, #min, Some(#max))? {
+                            e.check_fixed(#fixed)?;
+                        }
+                    })
+                })
+                .chain(attrs.checks.iter().cloned())
+                .collect::<Vec<_>>();
+
+            let fields_deserialize_impl = c
+                .fields
+                .iter()
+                .filter(|(_, (attributes, _))| fixed_value(attributes).is_none())
+                .map(|(field_name, (attributes, field_type))| {
+                    let fname = field_names.element(field_name);
+                    let ftype = Literal::string(field_name);
+
+                    let namespace = gen_namespace_match(attributes.namespace.as_deref(), ctx);
+                    let min = attributes.min();
+                    let max = match attributes.max() {
+                        Some(max) => quote! { Some(#max) },
+                        None => quote! { None },
+                    };
+
+                    // members of the substitution group are read in place of the element
+                    let occurrences = if attributes.substitutions.is_empty() {
+                        quote! { element.get_occurrences(#namespace, #ftype, #min, #max)? }
+                    } else {
+                        let members = attributes.substitutions.iter().map(|(element, _)| {
+                            let member_namespace = gen_namespace_match(Some(element.namespace()), ctx);
+                            let member_name = element.name();
+                            quote! { (#member_namespace, #member_name) }
+                        });
+                        quote! { element.get_occurrences_of(&[(#namespace, #ftype), #(#members),*], #min, #max)? }
+                    };
+
+                    let mapping = ctx.mapping(name, field_name, field_type);
+                    let parse = gen_simple_from_element(field_type, mapping, ctx);
+                    let substitutes = match mapping {
+                        Some(_) => vec![],
+                        None => substitutes(attributes, field_type, ctx),
+                    };
+                    let parse = if substitutes.is_empty() {
+                        parse
+                    } else {
+                        let field_kind = gen_simple(field_type, None, ctx);
+                        let arms = substitutes.iter().map(|(element, t)| {
+                            let member_namespace = gen_namespace_match(Some(element.namespace()), ctx);
+                            let member_name = element.name();
+                            let (type_namespace, type_name) = (t.namespace(), t.name());
+                            quote! {
+                                if #member_namespace.matches(e, #member_name) {
+                                    #field_kind::from_element_as(e, #type_namespace, #type_name)
+                                } else
+                            }
+                        });
+                        quote! {
+                            (#(#arms)* {
+                                #parse
+                            })
+                        }
+                    };
+
+                    if let Some(default) = gen_default(attributes, field_type, mapping) {
+                        // empty elements are given the default value as well
+                        return quote! {
+                            #fname: match #occurrences.into_iter().next() {
+                                Some(e) if e.get_text().is_some() => #parse?,
+                                _ => #default,
+                            },
+                        };
+                    }
+
+                    match Occurs::of(attributes) {
+                        Occurs::One => quote! {
+                            #fname: {
+                                let e = #occurrences[0];
+                                #parse?
+                            },
+                        },
+                        Occurs::Optional => quote! {
+                            #fname: match #occurrences.into_iter().next() {
+                                Some(e) if !e.is_nil() => Some(#parse?),
+                                _ => None,
+                            },
+                        },
+                        Occurs::Many => {
+                            let item = if attributes.nillable {
+                                quote! {
+                                    if e.is_nil() {
+                                        None
+                                    } else {
+                                        Some(#parse?)
+                                    }
+                                }
+                            } else {
+                                quote! { #parse? }
+                            };
+                            let collect = if bounded(attributes, ctx) {
+                                quote! { savon::bounded::BoundedVec::try_from(v)? }
+                            } else {
+                                quote! { v }
+                            };
+
+                            quote! {
+                                #fname: {
+                                    let mut v = vec![];
+                                    for e in #occurrences {
+                                        v.push(#item);
+                                    }
+                                    #collect
+                                },
+                            }
+                        }
+                    }
+                })
+                .chain(c.any.then(|| {
+                    let declared = c.fields.iter().flat_map(|(field_name, (attributes, _))| {
+                        let namespace = gen_namespace_match(attributes.namespace.as_deref(), ctx);
+                        let members = attributes.substitutions.iter().map(|(element, _)| {
+                            let member_namespace = gen_namespace_match(Some(element.namespace()), ctx);
+                            let member_name = element.name();
+                            quote! { (#member_namespace, #member_name) }
+                        });
+                        std::iter::once(quote! { (#namespace, #field_name) }).chain(members)
+                    });
+
+                    quote! {
+                        any: element.get_undeclared_children(&[#(#declared),*]),
+                    }
+                }))
+                .chain(attrs.from_element.iter().cloned())
+                .collect::<Vec<_>>();
+
+            let deserialize_impl = if fields_deserialize_impl.is_empty() && fixed_checks.is_empty()
+            {
+                quote! {
+                    impl savon::gen::FromElement for #type_name {
+                        fn from_element(_element: &xmltree::Element) -> Result<Self, savon::Error> {
+                            Ok(#type_name {
+                            })
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    impl savon::gen::FromElement for #type_name {
+                        fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                            #(#fixed_checks)*
+
+                            Ok(#type_name {
+                                #(#fields_deserialize_impl)*
+                            })
+                        }
+                    }
+                }
+            };
+
+            // schema defaults are used in place of the zero values
+            let field_defaults = c
+                .fields
+                .iter()
+                .filter(|(_, (attributes, _))| fixed_value(attributes).is_none())
+                .map(|(field_name, (attributes, field_type))| {
+                    let fname = field_names.element(field_name);
+                    let mapping = ctx.mapping(name, field_name, field_type);
+                    (fname, gen_default(attributes, field_type, mapping))
+                })
+                .collect::<Vec<_>>();
+            let (derive_default, default_impl) = if attrs.has_defaults
+                || field_defaults.iter().any(|(_, default)| default.is_some())
+            {
+                let field_defaults = field_defaults.into_iter().map(|(fname, default)| {
+                    let default = default.unwrap_or_else(|| quote! { Default::default() });
+                    quote! { #fname: #default, }
+                });
+                let any_default = c.any.then(|| quote! { any: Default::default(), });
+                let attribute_defaults = &attrs.defaults;
+
+                (
+                    None,
+                    Some(quote! {
+                        impl Default for #type_name {
+                            fn default() -> Self {
+                                #type_name {
+                                    #(#field_defaults)*
+                                    #any_default
+                                    #(#attribute_defaults)*
+                                }
+                            }
+                        }
+                    }),
+                )
+            } else {
+                (Some(quote! { Default, }), None)
+            };
+
+            let builder = ctx.builder_names.contains_key(name).then(|| {
+                let elements = c
+                    .fields
+                    .iter()
+                    .filter(|(_, (attributes, _))| fixed_value(attributes).is_none())
+                    .map(|(field_name, (attributes, field_type))| {
+                        let mapping = ctx.mapping(name, field_name, field_type);
+                        let ty = gen_simple(field_type, mapping, ctx);
+                        let default = gen_default(attributes, field_type, mapping);
+                        let kind = match (default, Occurs::of(attributes)) {
+                            (Some(default), _) => BuilderKind::Defaulted(ty, default),
+                            (None, Occurs::One) => BuilderKind::Required(ty),
+                            (None, Occurs::Optional) => BuilderKind::Optional(ty),
+                            (None, Occurs::Many) => BuilderKind::Repeated {
+                                item: if attributes.nillable {
+                                    quote! { Option<#ty> }
+                                } else {
+                                    ty
+                                },
+                                min: attributes.min(),
+                                bounded: bounded(attributes, ctx),
+                            },
+                        };
+                        BuilderField {
+                            name: field_names.element(field_name),
+                            xml_name: field_name.clone(),
+                            kind,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                gen_builder(name, c, &elements, &attrs.builder, ctx)
+            });
+
+            let doc = ctx.type_doc(name);
+            let serde = ctx.serde_container(quote! { default });
+            let variants = ctx
+                .variants
+                .get(name)
+                .map(|variants| gen_variants(name, variants, ctx));
+
+            quote! {
+                #doc
+                #[derive(Clone, Debug, #derive_default #derives)]
+                #serde
+                #vis struct #type_name {
+                    #(#fields)*
+                    #any_field
+                    #(#attribute_fields)*
+                }
+
+                #default_impl
+
+                #serialize_impl
+
+                #deserialize_impl
+
+                #variants
+
+                #builder
+            }
+        }
+        Type::Simple(t) => {
+            let ident = gen_simple(t, None, ctx);
+            let display = gen_simple_display(t);
+            let from_str = gen_value_from_str(t, name.name(), ctx);
+            let text_impl = gen_text_impl(&type_name);
+
+            let doc = ctx.type_doc(name);
+
+            quote! {
+                #doc
+                #[derive(Clone, Debug, Default, #derives)]
+                #serde
+                #vis struct #type_name(#vis #ident);
+
+                impl std::fmt::Display for #type_name {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        let v = &self.0;
+                        write!(f, "{}", #display)
+                    }
+                }
+
+                impl std::str::FromStr for #type_name {
+                    type Err = savon::Error;
+
+                    fn from_str(s: &str) -> Result<Self, Self::Err> {
+                        Ok(#type_name(#from_str?))
+                    }
+                }
+
+                #text_impl
+            }
+        }
+        Type::List(t) => {
+            let item_type = gen_simple(t, None, ctx);
+            let to_string = gen_simple_to_string(t, None);
+            let from_str = gen_value_from_str(t, name.name(), ctx);
+            let text_impl = gen_text_impl(&type_name);
+
+            let doc = ctx.type_doc(name);
+
+            quote! {
+                #doc
+                #[derive(Clone, Debug, Default, #derives)]
+                #serde
+                #vis struct #type_name(#vis Vec<#item_type>);
+
+                impl std::fmt::Display for #type_name {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        let items = self.0.iter().map(|v| #to_string).collect::<Vec<_>>();
+                        write!(f, "{}", items.join(" "))
+                    }
+                }
+
+                impl std::str::FromStr for #type_name {
+                    type Err = savon::Error;
+
+                    fn from_str(s: &str) -> Result<Self, Self::Err> {
+                        s.split_whitespace()
+                            .map(|s| #from_str)
+                            .collect::<Result<Vec<_>, _>>()
+                            .map(#type_name)
+                    }
+                }
+
+                #text_impl
+            }
+        }
+        Type::Union(members) => {
+            let mut variants: Vec<(Ident, &SimpleType)> = vec![];
+            for member in members {
+                let variant = union_variant(member);
+                if variants.iter().any(|(v, _)| *v == variant) {
+                    warn!("duplicate member type {member:?} of union {name}");
+                    continue;
+                }
+                variants.push((variant, member));
+            }
+
+            let variant_defs = variants.iter().map(|(variant, ty)| {
+                let ty = gen_simple(ty, None, ctx);
+                quote! { #variant(#ty), }
+            });
+            let display_arms = variants.iter().map(|(variant, ty)| {
+                let display = gen_simple_display(ty);
+                quote! { #type_name::#variant(v) => write!(f, "{}", #display), }
+            });
+            let parse_members = variants.iter().map(|(variant, ty)| {
+                let from_str = gen_value_from_str(ty, name.name(), ctx);
+                quote! {
+                    if let Ok(v) = #from_str {
+                        return Ok(#type_name::#variant(v));
+                    }
+                }
+            });
+            let default_variant = &variants[0].0;
+            let name_str = name.name();
+            let text_impl = gen_text_impl(&type_name);
+
+            let doc = ctx.type_doc(name);
+
+            quote! {
+                #doc
+                #[derive(Clone, Debug, #derives)]
+                #serde
+                #vis enum #type_name {
+                    #(#variant_defs)*
+                }
+
+                impl Default for #type_name {
+                    fn default() -> Self {
+                        #type_name::#default_variant(Default::default())
+                    }
+                }
+
+                impl std::fmt::Display for #type_name {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        match self {
+                            #(#display_arms)*
+                        }
+                    }
+                }
+
+                impl std::str::FromStr for #type_name {
+                    type Err = savon::Error;
+
+                    fn from_str(s: &str) -> Result<Self, Self::Err> {
+                        #(#parse_members)*
+                        Err(savon::rpser::xml::Error::UnexpectedUnionValue {
+                            name: #name_str.to_string(),
+                            given: s.to_string(),
+                        }.into())
+                    }
+                }
+
+                #text_impl
+            }
+        }
+        Type::Array(t) => {
+            let item_type = gen_simple(t, None, ctx);
+            let item_serialize = match t {
+                SimpleType::Complex(_) => {
+                    quote! { .with_attrs(i.to_attributes()).with_nodes(i.to_nodes()) }
+                }
+                _ => quote! { .with_text(i.to_string()) },
+            };
+            let item_deserialize = gen_simple_from_element(t, None, ctx);
+
+            let doc = ctx.type_doc(name);
+
+            quote! {
+                #doc
+                #[derive(Clone, Debug, Default, #derives)]
+                #serde
+                #vis struct #type_name(#vis Vec<Option<#item_type>>);
+
+                impl savon::gen::ToElements for #type_name {
+                    fn to_elements(&self) -> Vec<xmltree::Element> {
+                        self.0.iter().map(|i| match i {
+                            Some(i) => xmltree::Element::node("item")#item_serialize,
+                            None => xmltree::Element::node("item").with_attr("xsi:nil", "true"),
+                        }).collect()
+                    }
+                }
+
+                impl savon::gen::FromElement for #type_name {
+                    fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                        // items of an encoded array may use any element name, and be nil
+                        let mut v = vec![];
+                        for e in element.children.iter().filter_map(|c| c.as_element()) {
+                            v.push(if e.is_nil() { None } else { Some(#item_deserialize?) });
+                        }
+                        Ok(#type_name(v))
+                    }
+                }
+            }
+        }
+        Type::Import(_) => {
+            warn!("skipping imported type {name}");
+            quote! {}
+        }
+    }
+}
+
+/// Names of the operations of a port type, unique within it: overloaded
+/// operations are named after their input, or output, instead.
+fn operation_names(port_type: &PortType) -> Vec<(String, &Operation)> {
+    let mut names: Vec<(String, &Operation)> = vec![];
+
+    for operation in port_type.operations.iter() {
+        let overloaded = port_type
+            .operations
+            .iter()
+            .filter(|o| o.name == operation.name)
+            .count()
+            > 1;
+        let base = if overloaded {
+            [
+                &operation.input_name,
+                &operation.output_name,
+                &operation.input,
+                &operation.output,
+            ]
+            .into_iter()
+            .find_map(|name| name.as_ref())
+            .unwrap_or(&operation.name)
+            .clone()
+        } else {
+            operation.name.clone()
+        };
+
+        // methods are snake case, so names differing only in case collide too
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while names
+            .iter()
+            .any(|(n, _)| string::to_snake(n) == string::to_snake(&name))
+        {
+            name = format!("{base}{suffix}");
+            suffix += 1;
+        }
+        names.push((name, operation));
+    }
+
+    names
+}
+
+/// Client method of an operation.
+struct OperationMethod {
+    doc: TokenStream,
+    name: Ident,
+    input: Ident,
+    input_type: Ident,
+    output: TokenStream,
+    body: TokenStream,
+}
+
+/// Generates the client method of an operation of `port_type`, named `name`.
+/// Only operations initiated by the client, one-way and request-response, have
+/// one.
+fn gen_operation(
+    method: &Ident,
+    port_type: &PortType,
+    name: &str,
+    operation: &Operation,
+    wsdl: &Wsdl,
+    ctx: &Context,
+    names: &ItemNames,
+) -> Option<OperationMethod> {
+    let input = match (operation.transmission, operation.input.as_ref()) {
+        (Transmission::OneWay | Transmission::RequestResponse, Some(input)) => input,
+        (transmission, _) => {
+            warn!(
+                "skipping {transmission:?} operation {}, it is initiated by the service",
+                operation.name
+            );
+            return None;
+        }
+    };
+
+    // the operation element is the input part element, telling overloads apart,
+    // and the operation itself for RPC messages
+    let element = wsdl
+        .messages
+        .get(input)
+        .and_then(|message| message.element());
+    let target_namespace = Literal::string(
+        element
+            .map(|element| element.namespace())
+            .unwrap_or(&wsdl.target_namespace),
+    );
+    let input_name = ident::ident(&string::to_snake(input));
+    let input_type = names.messages[input.as_str()].clone();
+
+    let op_str = Literal::string(element.map_or(&operation.name, |element| element.name()));
+    let (http, awaited) = if ctx.options.blocking {
+        (quote! { savon::http::blocking }, None)
+    } else {
+        (quote! { savon::http }, Some(quote! { .await }))
+    };
+
+    let (output, body, input_name) = match (operation.output.as_ref(), operation.faults.as_ref()) {
+        (None, None) => (
+            quote! { Result<(), savon::Error> },
+            quote! {
+                #http::one_way(&self.client, &self.base_url, #target_namespace, #op_str, &#input_name)#awaited
+            },
+            input_name,
+        ),
+        (None, Some(_)) => {
+            let err_name = &names.faults[&(port_type.name.as_str(), name.to_string())].0;
+
+            (
+                quote! { Result<Result<(), #err_name>, savon::Error> },
+                quote! {
+                    #http::one_way_with_faults(&self.client, &self.base_url, #target_namespace, #op_str, &#input_name)#awaited
+                },
+                input_name,
+            )
+        }
+        (Some(out), None) => {
+            let out_name = &names.messages[out.as_str()];
+
+            (
+                quote! { Result<Result<#out_name, ()>, savon::Error> },
+                quote! {
+                    #http::request_response(&self.client, &self.base_url, #target_namespace, #op_str, &#input_name)#awaited
+                },
+                input_name,
+            )
+        }
+        (Some(out), Some(_)) => {
+            let out_name = &names.messages[out.as_str()];
+            let err_name = &names.faults[&(port_type.name.as_str(), name.to_string())].0;
+
+            (
+                quote! { Result<Result<#out_name, #err_name>, savon::Error> },
+                quote! {
+                    #http::request_response(&self.client, &self.base_url, #target_namespace, #op_str, &#input_name)#awaited
+                },
+                input_name,
+            )
+        }
+    };
+
+    Some(OperationMethod {
+        doc: gen_doc(&[operation.documentation.as_deref()]),
+        name: method.clone(),
+        input: input_name,
+        input_type,
+        output,
+        body,
+    })
+}
+
+/// Generates the trait of the operations of a client, implemented by the
+/// client, see `GenOptions::client_traits`.
+fn gen_client_trait(
+    trait_name: &Ident,
+    client_name: &Ident,
+    client: &Client,
+    operations: &[OperationMethod],
+    ctx: &Context,
+) -> TokenStream {
+    let vis = &ctx.vis;
+    let doc = format!(
+        " Operations of the `{}` port type, implemented by [`{}`].",
+        client.port_type.name, client_name
+    );
+
+    // async methods return `Send` futures, so that fakes may be used across tasks
+    let signatures = operations
+        .iter()
+        .map(|operation| {
+            let OperationMethod {
+                name,
+                input,
+                input_type,
+                output,
+                ..
+            } = operation;
+            let output = if ctx.options.blocking {
+                output.clone()
+            } else {
+                quote! { impl std::future::Future<Output = #output> + Send }
+            };
+            quote! { fn #name(&self, #input: #input_type) -> #output }
+        })
+        .collect::<Vec<_>>();
+    let docs = operations.iter().map(|operation| &operation.doc);
+    let calls = operations.iter().map(|operation| {
+        let OperationMethod { name, input, .. } = operation;
+        quote! { #client_name::#name(self, #input) }
+    });
+
+    quote! {
+        #[doc = #doc]
+        #[allow(dead_code)]
+        #vis trait #trait_name {
+            #(#docs #signatures;)*
+        }
+
+        impl #trait_name for #client_name {
+            #(#signatures {
+                #calls
+            })*
+        }
+    }
+}
+
+/// Whether an operation, named `name` within its port type, is given a client
+/// method, see `GenOptions::operations`.
+fn included(options: &GenOptions, name: &str, operation: &Operation) -> bool {
+    options.operations.as_ref().is_none_or(|operations| {
+        operations
+            .iter()
+            .any(|o| *o == operation.name || *o == name)
+    })
+}
+
+/// Rust names of the messages, and of the fault enums of the operations by
+/// port type and operation name, unique among the types.
+struct ItemNames<'a> {
+    messages: BTreeMap<&'a str, Ident>,
+    faults: BTreeMap<(&'a str, String), (Ident, &'a [String])>,
+}
+
+/// Client type generated for the ports of a service bound to the same port type.
+struct Client<'a> {
+    name: String,
+    location: Option<&'a str>,
+    port_type: &'a PortType,
+    /// Documentation of the service.
+    documentation: Option<&'a str>,
+}
+
+/// Clients of every service: one named after the service when all of its
+/// ports share a port type, typically SOAP 1.1 and 1.2 bindings of the same
+/// operations, otherwise one per port type, named after its first port.
+fn clients<'a>(wsdl: &'a Wsdl, options: &GenOptions) -> Vec<Client<'a>> {
+    let mut clients = vec![];
+
+    for service in wsdl.services.iter() {
+        let mut ports: Vec<(&Port, &PortType)> = vec![];
+        for port in service.ports.iter() {
+            let Some(port_type) = wsdl
+                .bindings
+                .get(&port.binding)
+                .and_then(|binding| wsdl.port_types.get(&binding.port_type))
+            else {
+                warn!("binding {} of port {} not found", port.binding, port.name);
+                continue;
+            };
+
+            if !ports.iter().any(|(_, p)| p.name == port_type.name) {
+                ports.push((port, port_type));
+            }
+        }
+
+        let single = ports.len() == 1;
+        clients.extend(ports.into_iter().map(|(port, port_type)| Client {
+            name: if single {
+                options.renamed(&service.name, || string::to_pascal(&service.name))
+            } else {
+                options.renamed(&port.name, || string::to_pascal(&port.name))
+            },
+            location: port.location.as_deref(),
+            port_type,
+            documentation: service.documentation.as_deref(),
+        }));
+    }
+
+    clients
+}
+
+/// Generates the code of the WSDL document at `path` into `{out}/example.rs`.
+pub fn gen_write(path: &str, out: &str) -> Result<(), GenError> {
+    Generator::new()
+        .output("example.rs")
+        .generate_file(path, out)
+        .map(|_| ())
+}
+
+/// Generates the types of the schema document at `path` into `{out}/types.rs`.
+pub fn gen_types_write(path: &str, out: &str) -> Result<(), GenError> {
+    Generator::new()
+        .output("types.rs")
+        .generate_file(path, out)
+        .map(|_| ())
+}
+
+/// Types referenced by a type: its base, the types of its fields and
+/// attributes, and the items or members of lists and unions.
+fn referenced_types(t: &Type) -> Vec<&QualifiedTypename> {
+    fn named(ty: &SimpleType) -> Option<&QualifiedTypename> {
+        match ty {
+            SimpleType::Complex(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    match t {
+        Type::Complex(c) => c
+            .fields
+            .iter()
+            .chain(c.attributes.iter())
+            .filter_map(|(_, (_, ty))| named(ty))
+            .chain(c.base.as_ref())
+            .collect(),
+        Type::Simple(ty) | Type::Array(ty) | Type::List(ty) => named(ty).into_iter().collect(),
+        Type::Union(members) => members.iter().filter_map(named).collect(),
+        Type::Import(_) => vec![],
+    }
+}
+
+/// Names of the generated types: the types named in `GenOptions::types`,
+/// along with the types they reference and their derived types, or all of
+/// them.
+fn selected_types(
+    types: &BTreeMap<QualifiedTypename, Type>,
+    ctx: &Context,
+) -> BTreeSet<QualifiedTypename> {
+    let Some(names) = ctx.options.types.as_ref() else {
+        return types.keys().cloned().collect();
+    };
+
+    let pending = types
+        .keys()
+        .filter(|n| names.iter().any(|name| name == n.name()))
+        .collect::<Vec<_>>();
+    for name in names.iter() {
+        if !pending.iter().any(|n| n.name() == name) {
+            warn!("type {name} not found");
+        }
+    }
+
+    closure(types, pending, ctx)
+}
+
+/// Types reachable from the operations given a client method: the types of
+/// their input, output and fault messages, along with the types they
+/// reference and their derived types.
+fn reachable_types(wsdl: &Wsdl, ctx: &Context) -> BTreeSet<QualifiedTypename> {
+    let roots = clients(wsdl, ctx.options)
+        .iter()
+        .flat_map(|client| operation_names(client.port_type))
+        .filter(|(name, operation)| included(ctx.options, name, operation))
+        .flat_map(|(_, operation)| {
+            [&operation.input, &operation.output]
+                .into_iter()
+                .flatten()
+                .chain(operation.faults.iter().flatten())
+        })
+        .filter_map(|message| wsdl.messages.get(message))
+        .map(|message| message_type(message, &wsdl.schemas))
+        .collect::<Vec<_>>();
+
+    closure(&wsdl.types, roots.iter().collect(), ctx)
+}
+
+/// The given types, along with the types they reference and their derived
+/// types, transitively.
+fn closure<'a>(
+    types: &'a BTreeMap<QualifiedTypename, Type>,
+    mut pending: Vec<&'a QualifiedTypename>,
+    ctx: &'a Context,
+) -> BTreeSet<QualifiedTypename> {
+    let mut selected = BTreeSet::new();
+    while let Some(name) = pending.pop() {
+        if !selected.insert(name.clone()) {
+            continue;
+        }
+        pending.extend(ctx.variants.get(name).into_iter().flatten());
+        pending.extend(types.get(name).map(referenced_types).unwrap_or_default());
+    }
+
+    selected
+}
+
+/// Generates the selected types, by namespace.
+fn gen_namespace_types(
+    types: &BTreeMap<QualifiedTypename, Type>,
+    selected: &BTreeSet<QualifiedTypename>,
+    ctx: &Context,
+) -> BTreeMap<String, Vec<TokenStream>> {
+    let mut namespaces = BTreeMap::<String, Vec<TokenStream>>::new();
+    for (name, t) in types.iter().filter(|(name, _)| selected.contains(name)) {
+        namespaces
+            .entry(name.namespace().to_string())
+            .or_default()
+            .push(gen_type(name, t, ctx));
+    }
+    namespaces
+}
+
+/// The generated code of a document, by kind.
+struct Parts {
+    vis: TokenStream,
+    prelude: TokenStream,
+    /// Types, by namespace.
+    types: BTreeMap<String, Vec<TokenStream>>,
+    messages: Vec<TokenStream>,
+    clients: Vec<TokenStream>,
+    /// Enums of the faults of the operations.
+    faults: Vec<TokenStream>,
+    /// Functions reading and writing documents of standalone schemas.
+    documents: Vec<TokenStream>,
+}
+
+impl Parts {
+    /// All of the code, in a single module.
+    fn single(self) -> TokenStream {
+        let Parts {
+            prelude,
+            types,
+            messages,
+            clients,
+            faults,
+            documents,
+            ..
+        } = self;
+        let types = types.into_values().flatten();
+
+        quote! {
+            #prelude
+
+            #(#types)*
+
+            #(#messages)*
+
+            #(#clients)*
+
+            #(#faults)*
+
+            #(#documents)*
+        }
+    }
+
+    /// The code split into files, by path relative to the module directory:
+    /// `mod.rs`, `types/mod.rs` and a `types/{namespace}.rs` file per
+    /// namespace, `messages.rs` with the messages and fault enums, and
+    /// `client.rs`. The items of every file are re-exported by `mod.rs`, and
+    /// files are included rather than declared as modules, so that `mod.rs`
+    /// can be included from `OUT_DIR`.
+    fn files(self) -> BTreeMap<PathBuf, TokenStream> {
+        let Parts {
+            vis,
+            prelude,
+            types,
+            messages,
+            clients,
+            faults,
+            documents,
+        } = self;
+        let mut files = BTreeMap::new();
+        // items of the other files are reached through `mod.rs`
+        let file = |items: TokenStream, root: TokenStream| {
+            quote! {
+                #prelude
+                #[allow(unused_imports)]
+                use #root::*;
+
+                #items
+            }
+        };
+
+        let mut scope = Scope::default();
+        let namespaces = types
+            .into_iter()
+            .map(|(namespace, items)| {
+                let module = scope.ident(&namespace_module(&namespace));
+                let path = format!("{module}.rs");
+                files.insert(
+                    PathBuf::from("types").join(&path),
+                    file(quote! { #(#items)* }, quote! { super::super }),
+                );
+                (module, path)
+            })
+            .collect::<Vec<_>>();
+        let (modules, paths): (Vec<_>, Vec<_>) = namespaces.into_iter().unzip();
+        files.insert(
+            PathBuf::from("types").join("mod.rs"),
+            quote! {
+                #(#vis mod #modules { include!(#paths); })*
+
+                #(#vis use #modules::*;)*
+            },
+        );
+
+        let mut modules = vec![quote! { types }];
+        let mut includes = vec!["types/mod.rs"];
+        if !messages.is_empty() || !faults.is_empty() {
+            files.insert(
+                PathBuf::from("messages.rs"),
+                file(quote! { #(#messages)* #(#faults)* }, quote! { super }),
+            );
+            modules.push(quote! { messages });
+            includes.push("messages.rs");
+        }
+        if !clients.is_empty() {
+            files.insert(
+                PathBuf::from("client.rs"),
+                file(quote! { #(#clients)* }, quote! { super }),
+            );
+            modules.push(quote! { client });
+            includes.push("client.rs");
+        }
+
+        let documents = (!documents.is_empty()).then(|| {
+            quote! {
+                #prelude
+
+                #(#documents)*
+            }
+        });
+        files.insert(
+            PathBuf::from("mod.rs"),
+            quote! {
+                #(#vis mod #modules { include!(#includes); })*
+
+                #(#vis use #modules::*;)*
+
+                #documents
+            },
+        );
+
+        files
+    }
+}
+
+/// Name of the module of the types of a namespace: the namespace URI
+/// without its scheme, in snake case.
+fn namespace_module(namespace: &str) -> String {
+    let name = ["https://", "http://", "urn:"]
+        .iter()
+        .find_map(|scheme| namespace.strip_prefix(scheme))
+        .unwrap_or(namespace);
+    match string::to_snake(name) {
+        module if module.is_empty() => "unqualified".to_string(),
+        module => module,
+    }
+}
+
+/// Generates the types of a standalone schema, and `read_{element}` and
+/// `write_{element}` functions for documents of its global elements.
+pub fn gen_types(xsd: &Xsd, options: &GenOptions) -> Result<TokenStream, GenError> {
+    gen_types_parts(xsd, options).map(Parts::single)
+}
+
+fn gen_types_parts(xsd: &Xsd, options: &GenOptions) -> Result<Parts, GenError> {
+    let ctx = Context::new(&xsd.types, &xsd.schemas, options)?;
+    let prelude = gen_prelude(options)?;
+    let selected = selected_types(&xsd.types, &ctx);
+    let vis = &ctx.vis;
+
+    let types = gen_namespace_types(&xsd.types, &selected, &ctx);
+
+    let mut functions = Scope::default();
+    let documents = xsd
+        .schemas
+        .iter()
+        .flat_map(|schema| schema.elements.iter())
+        .filter_map(|element| match &element.element_type {
+            SimpleType::Complex(t) if selected.contains(t) => Some((element, t)),
+            _ => None,
+        })
+        .map(|(element, t)| {
+            let type_name = ctx.type_ident(t);
+            let snake = string::to_snake(element.name.name());
+            let read = functions.ident(&format!("read_{}", snake));
+            let write = functions.ident(&format!("write_{}", snake));
+            let name = element.name.name();
+            let namespace = match element.name.namespace() {
+                "" => quote! { None },
+                ns => quote! { Some(#ns) },
+            };
+            let read_doc = format!(" Reads a `{}` document.", element.name);
+            let write_doc = format!(" Writes a `{}` document.", element.name);
+
+            quote! {
+                #[doc = #read_doc]
+                #vis fn #read(xml: &[u8]) -> Result<#type_name, savon::Error> {
+                    savon::gen::read_document(xml, #namespace, #name)
+                }
+
+                #[doc = #write_doc]
+                #vis fn #write(value: &#type_name) -> String {
+                    savon::gen::write_document(value, #namespace, #name)
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Parts {
+        vis: ctx.vis.clone(),
+        prelude,
+        types,
+        messages: vec![],
+        clients: vec![],
+        faults: vec![],
+        documents,
+    })
+}
+
+pub fn gen(wsdl: &Wsdl) -> Result<TokenStream, GenError> {
+    gen_with_options(wsdl, &GenOptions::default())
+}
+
+/// The type of a message: the type of its element if it is a schema type,
+/// otherwise the type generated under the element name, or the type of its
+/// parts.
+fn message_type(message: &Message, schemas: &[Schema]) -> QualifiedTypename {
+    let element = match &message.content {
+        MessageContent::Element(element) => element,
+        MessageContent::Parts(parts_type) => return parts_type.clone(),
+    };
+    schemas
+        .iter()
+        .flat_map(|schema| schema.elements.iter())
+        .find(|e| e.name == *element)
+        .and_then(|e| match &e.element_type {
+            SimpleType::Complex(t) => Some(t.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| element.clone())
+}
+
+pub fn gen_with_options(wsdl: &Wsdl, options: &GenOptions) -> Result<TokenStream, GenError> {
+    gen_parts(wsdl, options).map(Parts::single)
+}
+
+fn gen_parts(wsdl: &Wsdl, options: &GenOptions) -> Result<Parts, GenError> {
+    let ctx = Context::new(&wsdl.types, &wsdl.schemas, options)?;
+    let prelude = gen_prelude(options)?;
+    let mut selected = selected_types(&wsdl.types, &ctx);
+    // messages made of parts are generated along with the types of their parts
+    if options.types.is_some() {
+        for message in wsdl.messages.values() {
+            let MessageContent::Parts(parts_type) = &message.content else {
+                continue;
+            };
+            let parts_selected = wsdl
+                .types
+                .get(parts_type)
+                .map(referenced_types)
+                .unwrap_or_default()
+                .into_iter()
+                .all(|t| !wsdl.types.contains_key(t) || selected.contains(t));
+            if parts_selected {
+                selected.insert(parts_type.clone());
+            }
+        }
+    }
+    if options.prune_types {
+        let reachable = reachable_types(wsdl, &ctx);
+        selected.retain(|name| {
+            let used = reachable.contains(name);
+            if !used {
+                info!("type {name} is not used by the operations, dropped");
+            }
+            used
+        });
+    }
+    let (vis, derives) = (&ctx.vis, &ctx.derives);
+    let serde = ctx.serde_container(quote! {});
+
+    let types = gen_namespace_types(&wsdl.types, &selected, &ctx);
+
+    // with a subset of the types, messages of the other types are skipped
+    let subset = options.types.is_some() || options.prune_types;
+    let messages = wsdl
+        .messages
+        .iter()
+        .map(|(message_name, message)| (message_name, message_type(message, &wsdl.schemas)))
+        .filter(|(_, part_type)| !subset || selected.contains(part_type))
+        .collect::<BTreeMap<_, _>>();
+    let generated = |operation: &Operation| {
+        [&operation.input, &operation.output]
+            .into_iter()
+            .flatten()
+            .chain(operation.faults.iter().flatten())
+            .all(|message| messages.contains_key(message))
+    };
+    let included = |name: &str, operation: &Operation| included(options, name, operation);
+
+    // messages, clients and fault enums are named after the types
+    let mut scope = ctx.scope.clone();
+    let mut names = ItemNames {
+        messages: messages
+            .keys()
+            .map(
+                |message_name| match &wsdl.messages[message_name.as_str()].content {
+                    // the type of the parts is the message itself
+                    MessageContent::Parts(parts_type) => {
+                        (message_name.as_str(), ctx.type_ident(parts_type))
+                    }
+                    MessageContent::Element(_) => {
+                        let name =
+                            options.renamed(message_name, || string::to_pascal(message_name));
+                        (message_name.as_str(), scope.ident(&name))
+                    }
+                },
+            )
+            .collect(),
+        faults: BTreeMap::new(),
+    };
+    let clients = clients(wsdl, options)
+        .into_iter()
+        .map(|client| (scope.ident(&client.name), client))
+        .collect::<Vec<_>>();
+    let faults = wsdl
+        .port_types
+        .values()
+        .flat_map(|port_type| {
+            operation_names(port_type)
+                .into_iter()
+                .map(move |(name, operation)| (port_type, name, operation))
+        })
+        .filter(|(_, name, operation)| included(name, operation) && generated(operation))
+        .filter_map(|(port_type, name, operation)| {
+            Some((port_type, name, operation.faults.as_deref()?))
+        })
+        .collect::<Vec<_>>();
+    for (port_type, name, operation_faults) in faults.iter() {
+        // operations of the same name in several port types have their own enums
+        let shared = faults
+            .iter()
+            .filter(|(_, other, _)| string::to_pascal(other) == string::to_pascal(name))
+            .count()
+            > 1;
+        let op_error = if shared {
+            format!(
+                "{}{}Error",
+                string::to_pascal(&port_type.name),
+                string::to_pascal(name)
+            )
+        } else {
+            format!("{}Error", string::to_pascal(name))
+        };
+        names.faults.insert(
+            (port_type.name.as_str(), name.clone()),
+            (scope.ident(&op_error), operation_faults),
+        );
+    }
+    let client_traits = clients
+        .iter()
+        .map(|(client_name, _)| {
+            options
+                .client_traits
+                .then(|| scope.ident(&format!("{client_name}Api")))
+        })
+        .collect::<Vec<_>>();
+
+    let message_structs = messages
+        .iter()
+        .filter(|(message_name, _)| wsdl.messages[message_name.as_str()].element().is_some())
+        .map(|(message_name, part_type)| {
+            let mname = &names.messages[message_name.as_str()];
+            let iname = ctx.type_ident(part_type);
+
+            quote! {
+                #[derive(Clone, Debug, Default, #derives)]
+                #serde
+                #vis struct #mname(#vis #iname);
+
+                impl savon::gen::ToElements for #mname {
+                    fn to_elements(&self) -> Vec<xmltree::Element> {
+                        self.0.to_elements()
+                    }
+                }
+
+                impl savon::gen::FromElement for #mname {
+                    fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
+                        #iname::from_element(element).map(#mname)
+                    }
+                }
+            }
+        })
+        .collect();
+
+    let (http_client, asyncness) = if options.blocking {
+        (quote! { savon::internal::reqwest::blocking::Client }, None)
+    } else {
+        (
+            quote! { savon::internal::reqwest::Client },
+            Some(quote! { async }),
+        )
+    };
+    let clients = clients
+        .iter()
+        .zip(client_traits)
+        .map(|((client_name, client), client_trait)| {
+            let doc = gen_doc(&[
+                client.documentation,
+                client.port_type.documentation.as_deref(),
+                Some(&match client.location {
+                    Some(location) => format!(
+                        "Client of the `{}` port type at `{}`.",
+                        client.port_type.name, location
+                    ),
+                    None => format!("Client of the `{}` port type.", client.port_type.name),
+                }),
+            ]);
+            let mut methods = Scope::new(&["new", "with_client"]);
+            let operations = operation_names(client.port_type)
+                .into_iter()
+                .filter(|(name, operation)| included(name, operation) && generated(operation))
+                .filter_map(|(name, operation)| {
+                    let key = format!("{}.{name}", client.port_type.name);
+                    let method = methods.ident(&options.renamed(&key, || string::to_snake(&name)));
+                    gen_operation(
+                        &method,
+                        client.port_type,
+                        &name,
+                        operation,
+                        wsdl,
+                        &ctx,
+                        &names,
+                    )
+                })
+                .collect::<Vec<_>>();
+            let methods = operations.iter().map(|operation| {
+                let OperationMethod {
+                    doc,
+                    name,
+                    input,
+                    input_type,
+                    output,
+                    body,
+                } = operation;
+                quote! {
+                    #doc
+                    pub #asyncness fn #name(&self, #input: #input_type) -> #output {
+                        #body
+                    }
+                }
+            });
+            let client_trait = client_trait.map(|client_trait| {
+                gen_client_trait(&client_trait, client_name, client, &operations, &ctx)
+            });
+
+            quote! {
+                #doc
+                #vis struct #client_name {
+                    #vis base_url: String,
+                    #vis client: #http_client,
+                }
+
+                #[allow(dead_code)]
+                impl #client_name {
+                    pub fn new(base_url: String) -> Self {
+                        Self::with_client(base_url, #http_client::new())
+                    }
+
+                    pub fn with_client(base_url: String, client: #http_client) -> Self {
+                        #client_name {
+                            base_url,
+                            client,
+                        }
+                    }
+
+                    #(#methods)*
+                }
+
+                #client_trait
+            }
+        })
+        .collect();
+
+    let operation_faults = names
+        .faults
+        .values()
+        .map(|(op_error, faults)| {
+            let fault_names = faults
+                .iter()
+                .map(|fault| &names.messages[fault.as_str()])
+                .collect::<Vec<_>>();
+            // faults are told apart by their first element, the entry of the
+            // detail, which holds the parts of faults not made of an element
+            let arms = faults.iter().zip(fault_names.iter()).filter_map(|(fault, fault_name)| {
+                let message = &wsdl.messages[fault];
+                let (namespace, name) = match message.parts.first()? {
+                    Part::Element { element, .. } => (element.namespace(), element.name()),
+                    Part::Type { name, .. } => ("", name.as_str()),
+                };
+                let pattern = match namespace {
+                    "" => quote! { (None, #name) },
+                    ns => quote! { (Some(#ns), #name) },
+                };
+                let source = match message.content {
+                    MessageContent::Element(_) => quote! { entry },
+                    MessageContent::Parts(_) => quote! { detail },
+                };
+                Some(quote! {
+                    #pattern => Some(
+                        <#fault_name as savon::gen::FromElement>::from_element(#source).map(#op_error::#fault_name)
+                    ),
+                })
+            });
+
+            quote! {
+                #[derive(Clone, Debug, #derives)]
+                #serde
+                #vis enum #op_error {
+                    #(#fault_names(#fault_names),)*
+                }
+
+                impl savon::gen::FromFault for #op_error {
+                    fn from_fault(detail: &xmltree::Element) -> Option<Result<Self, savon::Error>> {
+                        let entry = detail.children.iter().find_map(|c| c.as_element())?;
+                        match (entry.namespace.as_deref(), entry.name.as_str()) {
+                            #(#arms)*
+                            _ => None,
+                        }
+                    }
+                }
+            }
+        })
+        .collect();
+
+    Ok(Parts {
+        vis: ctx.vis.clone(),
+        prelude,
+        types,
+        messages: message_structs,
+        clients,
+        faults: operation_faults,
+        documents: vec![],
+    })
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+mod tests {
+    use super::*;
+    const WIKIPEDIA_WSDL: &[u8] = include_bytes!("../../assets/wikipedia-example.wsdl");
+    const EXAMPLE_WSDL: &[u8] = include_bytes!("../../assets/example.wsdl");
+
+    #[test]
+    fn example() {
+        let wsdl = parse(EXAMPLE_WSDL).unwrap();
+        println!("wsdl: {:?}", wsdl);
+
+        let res = gen(&wsdl).unwrap();
+
+        println!("generated:\n{}", res);
+    }
+
+    #[test]
+    fn wildcards() {
+        let wsdl = parse(include_bytes!("../../assets/qualified.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap().to_string();
+
+        assert!(res.contains("pub any : Vec < xmltree :: Element >"));
+        assert!(res.contains("get_undeclared_children"));
+        assert!(res.contains("get_undeclared_attributes"));
+    }
+
+    #[test]
+    fn derived_types() {
+        let wsdl = parse(include_bytes!("../../assets/polymorphic.wsdl")).unwrap();
+        let options = GenOptions::default();
+        let ctx = Context::new(&wsdl.types, &wsdl.schemas, &options).unwrap();
+        let variants = ctx
+            .variants
+            .iter()
+            .map(|(name, variants)| {
+                let variants = variants.iter().map(|v| v.name()).collect::<Vec<_>>();
+                (name.name(), variants)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            variants,
+            vec![
+                (
+                    "BankTransferPayment",
+                    vec!["BankTransferPayment", "SepaTransferPayment"]
+                ),
+                (
+                    "Payment",
+                    vec![
+                        "BankTransferPayment",
+                        "CreditCardPayment",
+                        "SepaTransferPayment"
+                    ]
+                ),
+            ]
+        );
+
+        let res = gen(&wsdl).unwrap().to_string();
+        assert!(res.contains("pub enum PaymentKind"));
+        assert!(res.contains("pub enum BankTransferPaymentKind"));
+        assert!(!res.contains("pub enum CreditCardPaymentKind"));
+        assert!(res.contains("pub payments : Vec < PaymentKind >"));
+    }
+
+    #[test]
+    fn identifiers() {
+        let wsdl = parse(include_bytes!("../../assets/identifiers.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap().to_string();
+
+        assert!(res.contains("pub r#type : String"));
+        assert!(res.contains("pub self_ : String"));
+        assert!(res.contains("pub r#ref : Option < String2 >"));
+        assert!(res.contains("pub kebab_name : String"));
+        assert!(res.contains("pub änderungs_datum : String"));
+        assert!(res.contains("pub id2 : Option < String >"));
+        assert!(res.contains("pub struct GetSettings2 (pub GetSettings)"));
+        assert!(res.contains("pub struct Settings2 {"));
+        assert!(res.contains("pub async fn new2 (& self"));
+    }
+
+    #[test]
+    fn renames() {
+        let wsdl = parse(include_bytes!("../../assets/identifiers.wsdl")).unwrap();
+        let res = Generator::new()
+            .rename("String", "Text")
+            .rename("Settings.kebab-name", "kebab")
+            .rename("Settings-Port.getSettings", "fetch_settings")
+            .generate(&wsdl)
+            .unwrap()
+            .to_string();
+
+        assert!(res.contains("pub struct Text {"));
+        assert!(res.contains("pub r#ref : Option < Text >"));
+        assert!(res.contains("pub kebab : String"));
+        assert!(res.contains("pub dotted_name : i64"));
+        assert!(res.contains("pub async fn fetch_settings (& self"));
+    }
+
+    #[test]
+    fn mixed_content() {
+        let wsdl = parse(include_bytes!("../../assets/mixed.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap().to_string();
+
+        assert!(res.contains("pub content : Vec < RichTextSegment >"));
+        assert!(
+            res.contains("pub enum RichTextSegment { Text (String) , B (String) , Link (Link) , }")
+        );
+    }
+
+    #[test]
+    fn defaults() {
+        let wsdl = parse(include_bytes!("../../assets/defaults.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap().to_string();
+
+        assert!(res.contains("impl Default for Settings"));
+        assert!(res.contains("page_size : 25"));
+        assert!(res.contains("pub scope : String"));
+        // fixed values are written and checked, but not stored
+        assert!(!res.contains("pub schema"));
+        assert!(res.contains("check_fixed_attribute (\"version\" , \"1.0\")"));
+    }
+
+    #[test]
+    fn lists_and_unions() {
+        let wsdl = parse(include_bytes!("../../assets/lists.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap().to_string();
+
+        assert!(res.contains("pub struct Years (pub Vec < i64 >) ;"));
+        assert!(res.contains("s . split_whitespace ()"));
+        assert!(res.contains("pub enum Limit { Int (i64) , Keyword (Keyword) , }"));
+        assert!(res.contains("impl std :: str :: FromStr for Keyword"));
+        assert!(res.contains("impl savon :: gen :: FromElement for Limit"));
+    }
+
+    #[test]
+    fn serde() {
+        let wsdl = parse(include_bytes!("../../assets/lists.wsdl")).unwrap();
+        let options = GenOptions {
+            serde: true,
+            ..Default::default()
+        };
+        let res = gen_with_options(&wsdl, &options).unwrap().to_string();
+
+        assert!(res.contains("# [derive (Clone , Debug , Default , savon :: internal :: serde :: Serialize , savon :: internal :: serde :: Deserialize ,)] # [serde (crate = \"savon::internal::serde\" , default)] pub struct GetReport"));
+        assert!(res.contains("# [serde (rename = \"Years\")] pub years : Years"));
+        assert!(res
+            .contains("# [serde (crate = \"savon::internal::serde\" , untagged)] pub enum Limit"));
+        assert!(res.contains("# [serde (crate = \"savon::internal::serde\" ,)] pub struct Years"));
+
+        let wsdl = parse(include_bytes!("../../assets/qualified.wsdl")).unwrap();
+        let res = gen_with_options(&wsdl, &options).unwrap().to_string();
+        assert!(res.contains("# [serde (skip)] pub any : Vec < xmltree :: Element >"));
+    }
+
+    #[test]
+    fn clients() {
+        let wsdl = parse(include_bytes!("../../assets/multiport.wsdl")).unwrap();
+        let clients = super::clients(&wsdl, &GenOptions::default())
+            .iter()
+            .map(|c| (c.name.to_string(), c.port_type.name.as_str()))
+            .collect::<Vec<_>>();
+
+        // SOAP 1.1 and 1.2 ports of the same port type share a client
+        assert_eq!(
+            clients,
+            vec![
+                ("InventoryPublic".to_string(), "InventorySoap"),
+                ("InventoryAdmin".to_string(), "InventoryAdminSoap"),
+                ("Monitoring".to_string(), "MonitoringSoap"),
+            ]
+        );
+
+        let res = gen(&wsdl).unwrap().to_string();
+        let admin = res.find("pub struct InventoryAdmin").unwrap();
+        assert!(res[admin..].contains("pub async fn set_stock"));
+        assert!(!res[admin..].contains("pub async fn get_stock"));
+    }
+
+    #[test]
+    fn generator() {
+        let wsdl = parse(include_bytes!("../../assets/multiport.wsdl")).unwrap();
+        let res = Generator::new()
+            .module("inventory")
+            .derive("PartialEq")
+            .visibility("pub(crate)")
+            .type_prefix("Inv")
+            .type_suffix("Type")
+            .types(["GetStock", "GetStockResponse", "SetStock"])
+            .operations(["GetStock"])
+            .blocking(true)
+            .crate_path("crate::soap")
+            .generate(&wsdl)
+            .unwrap()
+            .to_string();
+
+        assert!(res.starts_with("pub (crate) mod inventory"));
+        assert!(res.contains("use crate :: soap as savon ;"));
+        assert!(res.contains(
+            "# [derive (Clone , Debug , Default , PartialEq ,)] pub (crate) struct InvGetStockType"
+        ));
+        assert!(res.contains("pub (crate) struct GetStockSoapIn (pub (crate) InvGetStockType)"));
+        // `Ping` is not generated, nor the operation using it
+        assert!(!res.contains("InvPingType"));
+        assert!(!res.contains("PingSoapIn"));
+        assert!(res.contains("reqwest :: blocking :: Client"));
+        assert!(res.contains("pub fn get_stock"));
+        assert!(res.contains("savon :: http :: blocking :: request_response"));
+        assert!(!res.contains("fn set_stock"));
+
+        for generator in [
+            Generator::new().derive("Partial Eq"),
+            Generator::new().visibility("public"),
+            Generator::new().type_prefix("My-"),
+            Generator::new().crate_path("::"),
+        ] {
+            assert!(matches!(
+                generator.generate(&wsdl),
+                Err(GenError::InvalidOption { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn client_traits() {
+        let wsdl = parse(include_bytes!("../../assets/multiport.wsdl")).unwrap();
+        let res = Generator::new()
+            .client_traits(true)
+            .generate(&wsdl)
+            .unwrap()
+            .to_string();
+
+        assert!(res.contains("pub trait InventoryPublicApi"));
+        assert!(res.contains("fn get_stock (& self , get_stock_soap_in : GetStockSoapIn) -> impl std :: future :: Future < Output = Result < Result < GetStockSoapOut , () > , savon :: Error > > + Send ;"));
+        assert!(res.contains("impl InventoryPublicApi for InventoryPublic"));
+        assert!(res.contains("InventoryPublic :: get_stock (self , get_stock_soap_in)"));
+        assert!(res.contains("pub trait InventoryAdminApi"));
+
+        let res = Generator::new()
+            .client_traits(true)
+            .blocking(true)
+            .generate(&wsdl)
+            .unwrap()
+            .to_string();
+        assert!(res.contains("fn set_stock (& self , set_stock_soap_in : SetStockSoapIn) -> Result < () , savon :: Error > ;"));
+
+        let res = gen(&wsdl).unwrap().to_string();
+        assert!(!res.contains("trait"));
+    }
+
+    #[test]
+    fn pruned_types() {
+        let wsdl = parse(include_bytes!("../../assets/multiport.wsdl")).unwrap();
+        let generator = Generator::new().operations(["GetStock"]).prune_types(true);
+        let unused = generator.unused_types(&wsdl).unwrap();
+        let unused = unused.iter().map(|t| t.name()).collect::<Vec<_>>();
+        assert_eq!(unused, ["Ping", "SetStock"]);
+
+        let res = generator.generate(&wsdl).unwrap().to_string();
+        assert!(res.contains("pub struct GetStockResponse"));
+        assert!(!res.contains("pub struct SetStock"));
+        assert!(!res.contains("SetStockSoapIn"));
+        assert!(!res.contains("PingSoapIn"));
+        assert!(res.contains("fn get_stock"));
+
+        // derived types and substitution group members are reachable from the base type
+        let wsdl = parse(include_bytes!("../../assets/polymorphic.wsdl")).unwrap();
+        let generator = Generator::new().prune_types(true);
+        assert!(generator.unused_types(&wsdl).unwrap().is_empty());
+        let res = generator.generate(&wsdl).unwrap().to_string();
+        assert!(res.contains("pub struct SepaTransferPayment"));
+    }
+
+    #[test]
+    fn directory_layout() {
+        let out_dir = std::env::temp_dir().join(format!("savon-layout-{}", std::process::id()));
+        let read = |path: &str| std::fs::read_to_string(out_dir.join(path)).unwrap();
+
+        let mod_path = Generator::new()
+            .generate_dir("../assets/qualified.wsdl", &out_dir)
+            .unwrap();
+        assert_eq!(mod_path, out_dir.join("qualified/mod.rs"));
+        assert!(
+            read("qualified/mod.rs").contains("pub mod types {\n    include!(\"types/mod.rs\");")
+        );
+        assert!(read("qualified/mod.rs").contains("pub use client::*;"));
+        assert!(read("qualified/types/mod.rs").contains("pub mod tempuri_org_orders {"));
+        assert!(read("qualified/types/tempuri_org_addresses.rs").contains("use super::super::*;"));
+        assert!(read("qualified/messages.rs").contains("pub struct"));
+        assert!(read("qualified/client.rs").contains("pub struct"));
+
+        // files which are no longer generated are removed
+        Generator::new()
+            .output("qualified.rs")
+            .generate_dir("../assets/order.xsd", &out_dir)
+            .unwrap();
+        assert!(read("qualified/mod.rs").contains("pub fn read_order"));
+        assert!(out_dir
+            .join("qualified/types/tempuri_org_order.rs")
+            .exists());
+        assert!(!out_dir
+            .join("qualified/types/tempuri_org_orders.rs")
+            .exists());
+        assert!(!out_dir.join("qualified/client.rs").exists());
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    #[test]
+    fn overloaded_operations() {
+        let wsdl = parse(include_bytes!("../../assets/overloaded.wsdl")).unwrap();
+        let names = operation_names(&wsdl.port_types["CatalogSoap"])
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "SearchByName",
+                "SearchById",
+                "PriceChanged",
+                "PriceChangedWithConfirmation",
+                "Log"
+            ]
+        );
+
+        let res = gen(&wsdl).unwrap().to_string();
+        assert!(res.contains("pub async fn search_by_name"));
+        assert!(res.contains("pub async fn search_by_id"));
+        // each overload sends its own input element
+        assert!(res.contains(r#""SearchByName" , & search_by_name_in"#));
+        assert!(res.contains(r#""SearchById" , & search_by_id_in"#));
+        // operations initiated by the service have no client method
+        assert!(!res.contains("pub async fn price_changed"));
+        assert!(res.contains("pub async fn log"));
+    }
+
+    #[test]
+    fn wsdl20() {
+        let wsdl = parse(include_bytes!("../../assets/wsdl20.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap().to_string();
+
+        assert!(res.contains("pub struct Weather"));
+        assert!(res.contains("pub struct GetForecastInput (pub GetForecast) ;"));
+        assert!(res.contains("pub async fn submit_report"));
+        assert!(res.contains("pub enum GetForecastError { UnknownCityFault (UnknownCityFault) , }"));
+    }
+
+    #[test]
+    fn faults_per_port_type() {
+        let wsdl = parse(include_bytes!("../../assets/faults.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap().to_string();
+
+        // both port types have a Search operation, with different faults
+        assert!(res.contains(
+            "pub enum PublicSoapSearchError { InvalidQueryFault (InvalidQueryFault) , }"
+        ));
+        assert!(res.contains(
+            "pub enum AdminSoapSearchError { InvalidQueryFault (InvalidQueryFault) , AccessDeniedFault (AccessDeniedFault) , }"
+        ));
+        assert!(!res.contains("pub enum SearchError"));
+        assert!(res.contains(
+            "Result < Result < SearchSoapOut , PublicSoapSearchError > , savon :: Error >"
+        ));
+        assert!(res.contains(
+            "Result < Result < SearchSoapOut , AdminSoapSearchError > , savon :: Error >"
+        ));
+    }
+
+    #[test]
+    fn one_way_faults() {
+        let wsdl = parse(include_bytes!("../../assets/faults.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap().to_string();
+
+        // one-way operations declaring faults have a method decoding them
+        assert!(res.contains("pub enum ReindexError { AccessDeniedFault (AccessDeniedFault) , }"));
+        assert!(res.contains(
+            "pub async fn reindex (& self , reindex_soap_in : ReindexSoapIn) -> Result < Result < () , ReindexError > , savon :: Error >"
+        ));
+    }
+
+    #[test]
+    fn standalone_types() {
+        let xsd = crate::xsd::parse(include_bytes!("../../assets/order.xsd")).unwrap();
+        let res = gen_types(&xsd, &GenOptions::default()).unwrap().to_string();
+
+        assert!(res.contains("pub struct Order"));
+        assert!(
+            res.contains("pub fn read_order (xml : & [u8]) -> Result < Order , savon :: Error >")
+        );
+        assert!(res.contains(
+            "savon :: gen :: write_document (value , Some (\"http://tempuri.org/order\") , \"Order\")"
+        ));
+        assert!(!res.contains("reqwest"));
+    }
+
+    #[test]
+    fn type_mappings() {
+        let xsd = crate::xsd::parse(include_bytes!("../../assets/mappings.xsd")).unwrap();
+        let generator = Generator::new()
+            .map_type(
+                "{http://tempuri.org/shipment}Guid",
+                "uuid::Uuid",
+                Conversion::FromStr,
+            )
+            .map_type("xsd:long", "i64", Conversion::FromStr)
+            .map_type(
+                "Shipment.Parcels",
+                "crate::ParcelId",
+                Conversion::Converter("crate::ParcelIdText".to_string()),
+            )
+            .map_type("Shipment.Destination", "crate::Place", Conversion::Element);
+        let res = generator.generate_types(&xsd).unwrap().to_string();
+
+        assert!(res.contains("pub id : uuid :: Uuid"));
+        assert!(res.contains("pub weight : i64"));
+        assert!(res.contains("pub parcels : Vec < crate :: ParcelId >"));
+        assert!(res.contains("pub destination : crate :: Place"));
+        assert!(res.contains("pub tracking : Option < uuid :: Uuid >"));
+        assert!(res.contains(
+            "< savon :: gen :: DisplayFromStr as savon :: gen :: Convert < uuid :: Uuid >> :: from_text (s)"
+        ));
+        assert!(res.contains(
+            "< crate :: ParcelIdText as savon :: gen :: Convert < crate :: ParcelId >> :: to_text (v)"
+        ));
+        assert!(
+            res.contains("< crate :: Place as savon :: gen :: FromElement > :: from_element (e)")
+        );
+
+        let invalid = Generator::new().map_type("Guid", "not a type", Conversion::FromStr);
+        assert!(matches!(
+            invalid.generate_types(&xsd),
+            Err(GenError::InvalidOption {
+                option: "type mapping",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn builders() {
+        let xsd = crate::xsd::parse(include_bytes!("../../assets/order.xsd")).unwrap();
+        let res = gen_types(&xsd, &GenOptions::default()).unwrap().to_string();
+        assert!(!res.contains("OrderBuilder"));
+
+        let res = Generator::new()
+            .builders(true)
+            .generate_types(&xsd)
+            .unwrap()
+            .to_string();
+        assert!(res.contains("pub struct OrderBuilder"));
+        assert!(res.contains("pub fn builder () -> OrderBuilder"));
+        assert!(
+            res.contains("pub fn with_customer (mut self , value : impl Into < String >) -> Self")
+        );
+        assert!(res.contains("pub fn push_line (mut self , item : impl Into < Line >) -> Self"));
+        assert!(res.contains("pub fn with_id (mut self , value : impl Into < i64 >) -> Self"));
+        assert!(res.contains(
+            "customer : self . customer . ok_or (savon :: gen :: MissingField { type_name : \"Order\" , field : \"Customer\" , }) ?"
+        ));
+        assert!(res.contains("pub fn build (self) -> Result < Order , savon :: Error >"));
+    }
+
+    #[test]
+    fn documentation() {
+        let wsdl = parse(include_bytes!("../../assets/documented.wsdl")).unwrap();
+        let res = gen(&wsdl).unwrap().to_string();
+
+        assert!(res.contains(
+            "# [doc = \" A book of the catalog.\"] # [doc = \"\"] \
+             # [doc = \" Books are identified by their ISBN.\"] # [doc = \"\"] \
+             # [doc = \" Qualified type: http://tempuri.org/library:Book\"]"
+        ));
+        assert!(res.contains("# [doc = \" Identifier of the book.\"]"));
+        assert!(res.contains("# [doc = \" Whether a copy can be borrowed.\"]"));
+        assert!(res.contains(
+            "# [doc = \" Finds a book by its ISBN, if the library has it.\"] pub async fn find_book"
+        ));
+        assert!(res.contains("# [doc = \" The city library.\"]"));
+    }
+
+    #[test]
+    fn occurrences() {
+        use crate::wsdl::Occurence;
+
+        let attributes = |nillable, min_occurs, max_occurs| TypeAttribute {
+            nillable,
+            min_occurs,
+            max_occurs,
+            ..Default::default()
+        };
+
+        assert_eq!(Occurs::of(&attributes(false, None, None)), Occurs::One);
+        assert_eq!(
+            Occurs::of(&attributes(
+                false,
+                Some(Occurence::Num(1)),
+                Some(Occurence::Num(1))
+            )),
+            Occurs::One
+        );
+        assert_eq!(
+            Occurs::of(&attributes(false, Some(Occurence::Num(0)), None)),
+            Occurs::Optional
+        );
+        assert_eq!(Occurs::of(&attributes(true, None, None)), Occurs::Optional);
+        assert_eq!(
+            Occurs::of(&attributes(false, None, Some(Occurence::Unbounded))),
+            Occurs::Many
+        );
+        assert_eq!(
+            Occurs::of(&attributes(
+                true,
+                Some(Occurence::Num(0)),
+                Some(Occurence::Num(3))
+            )),
+            Occurs::Many
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Valid, unique Rust identifiers for the names found in WSDL and XML schema
+//! documents.
+//!
+//! Characters which may not occur in an identifier are replaced by `_`, a
+//! leading digit is prefixed with `_`, keywords become raw identifiers, and
+//! `self`, `Self`, `super` and `crate`, which cannot be raw, get a `_` suffix.
+//! Names colliding within a scope get a numeric suffix: `Item`, `Item2`, etc.
+
+use proc_macro2::{Ident, Span};
+use std::collections::BTreeSet;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+/// Keywords, strict and reserved, which are valid raw identifiers.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Keywords which cannot be raw identifiers.
+const NOT_RAW: &[&str] = &["_", "crate", "self", "Self", "super"];
+
+/// `name` with the characters which may not occur in an identifier replaced
+/// by `_`, and a `_` prefix if it does not start with a letter or `_`.
+pub fn sanitize(name: &str) -> String {
+    let mut sanitized = name
+        .chars()
+        .map(|c| if is_xid_continue(c) { c } else { '_' })
+        .collect::<String>();
+
+    match sanitized.chars().next() {
+        Some(c) if is_xid_start(c) || c == '_' => {}
+        _ => sanitized.insert(0, '_'),
+    }
+    if NOT_RAW.contains(&sanitized.as_str()) {
+        sanitized.push('_');
+    }
+
+    sanitized
+}
+
+/// An identifier for `name`, sanitized, and raw if it is a keyword.
+pub fn ident(name: &str) -> Ident {
+    let name = sanitize(name);
+    if KEYWORDS.contains(&name.as_str()) {
+        Ident::new_raw(&name, Span::call_site())
+    } else {
+        Ident::new(&name, Span::call_site())
+    }
+}
+
+/// Names taken within a scope: the types of a module, the fields of a
+/// struct, the variants of an enum or the methods of a client.
+#[derive(Clone, Debug, Default)]
+pub struct Scope {
+    taken: BTreeSet<String>,
+}
+
+impl Scope {
+    /// A scope where the `reserved` names are already taken, e.g. the names
+    /// the generated code relies on.
+    pub fn new(reserved: &[&str]) -> Self {
+        Scope {
+            taken: reserved.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    /// Takes an identifier for `name`, suffixed with the first number from 2
+    /// making it unique within the scope.
+    pub fn ident(&mut self, name: &str) -> Ident {
+        let base = sanitize(name);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !self.taken.insert(name.clone()) {
+            name = format!("{base}{suffix}");
+            suffix += 1;
+        }
+
+        ident(&name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(ident("type").to_string(), "r#type");
+        assert_eq!(ident("match").to_string(), "r#match");
+        assert_eq!(ident("self").to_string(), "self_");
+        assert_eq!(ident("Self").to_string(), "Self_");
+        assert_eq!(ident("kebab-case.name").to_string(), "kebab_case_name");
+        assert_eq!(ident("3d").to_string(), "_3d");
+        assert_eq!(ident("").to_string(), "__");
+        assert_eq!(ident("straße").to_string(), "straße");
+    }
+
+    #[test]
+    fn scopes() {
+        let mut scope = Scope::new(&["String"]);
+        let names = ["String", "Item", "Item", "Item2", "item-id", "item_id"]
+            .iter()
+            .map(|name| scope.ident(name).to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec!["String2", "Item", "Item2", "Item22", "item_id", "item_id2"]
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! WSDL and XML schema parsing, and generation of the code of their types,
+//! messages and clients. The generated code depends on the `savon` crate,
+//! which re-exports this one.
+
+#[macro_use]
+extern crate log;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+
+pub mod gen;
+pub mod ident;
+pub mod string;
+pub mod wsdl;
+pub mod xsd;
@@ -0,0 +1,118 @@
+//! Case conversion of the names found in WSDL and XML schema documents.
+//!
+//! Names are split into words at separators (any character which is not a
+//! letter or a digit), at lowercase to uppercase transitions, at the end of
+//! acronyms (`HTTPServer` is `HTTP` and `Server`) and after digits (`URL2Id`
+//! is `URL2` and `Id`). Letters without case, as in most non-Latin scripts,
+//! are treated like lowercase ones.
+
+/// The words of `s`.
+pub fn words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = vec![];
+    let mut word = String::new();
+
+    for (i, &current) in chars.iter().enumerate() {
+        if !current.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        // an uppercase letter starts a word, unless it continues an acronym
+        if !word.is_empty() && current.is_uppercase() {
+            let previous = chars[i - 1];
+            let next_lowercase = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if !previous.is_uppercase() || next_lowercase {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+
+        word.push(current);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+/// `s` in snake case: `HTTPServerURL2Id` is `http_server_url2_id`.
+pub fn to_snake(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// `s` in pascal case: `getV2APIKey` is `GetV2ApiKey`.
+pub fn to_pascal(s: &str) -> String {
+    words(s).iter().map(|word| capitalize(word)).collect()
+}
+
+/// `s` in camel case: `HTTPServerURL2Id` is `httpServerUrl2Id`.
+pub fn to_camel(s: &str) -> String {
+    words(s)
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            if i == 0 {
+                word.to_lowercase()
+            } else {
+                capitalize(word)
+            }
+        })
+        .collect()
+}
+
+/// `word` with its first letter in uppercase and the others in lowercase.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.as_str().to_lowercase().chars())
+            .collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snake_case() {
+        assert_eq!(to_snake("HTTPServerURL2Id"), "http_server_url2_id");
+        assert_eq!(to_snake("getV2APIKey"), "get_v2_api_key");
+        assert_eq!(to_snake("already_snake"), "already_snake");
+        assert_eq!(to_snake("kebab-case"), "kebab_case");
+        assert_eq!(to_snake("GetLastTradePrice"), "get_last_trade_price");
+        assert_eq!(to_snake("ArrayOf_xsd_string"), "array_of_xsd_string");
+        assert_eq!(to_snake("ÄnderungsDatum"), "änderungs_datum");
+        assert_eq!(to_snake("名前ID"), "名前_id");
+        assert_eq!(to_snake("2nd"), "2nd");
+    }
+
+    #[test]
+    fn pascal_case() {
+        assert_eq!(to_pascal("HTTPServerURL2Id"), "HttpServerUrl2Id");
+        assert_eq!(to_pascal("getV2APIKey"), "GetV2ApiKey");
+        assert_eq!(to_pascal("already_snake"), "AlreadySnake");
+        assert_eq!(to_pascal("kebab-case"), "KebabCase");
+        assert_eq!(
+            to_pascal("ArrayOf_tns1_RemoteSpace"),
+            "ArrayOfTns1RemoteSpace"
+        );
+        assert_eq!(to_pascal("straße"), "Straße");
+    }
+
+    #[test]
+    fn camel_case() {
+        assert_eq!(to_camel("HTTPServerURL2Id"), "httpServerUrl2Id");
+        assert_eq!(to_camel("already_snake"), "alreadySnake");
+        assert_eq!(to_camel("GetSpaces"), "getSpaces");
+    }
+}
@@ -0,0 +1,2052 @@
+//! WSDL inspection helpers.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    str::FromStr,
+};
+use xmltree::Element;
+
+#[derive(Debug)]
+pub enum WsdlError {
+    Parse(xmltree::ParseError),
+    ElementNotFound(&'static str),
+    AttributeNotFound(&'static str),
+    NotAnElement,
+    Empty,
+}
+
+impl From<xmltree::ParseError> for WsdlError {
+    fn from(error: xmltree::ParseError) -> Self {
+        WsdlError::Parse(error)
+    }
+}
+
+/// WSDL document.
+#[derive(Debug)]
+pub struct Wsdl {
+    pub name: String,
+    pub target_namespace: String,
+
+    pub schemas: Vec<Schema>,
+    pub types: BTreeMap<QualifiedTypename, Type>,
+    pub messages: BTreeMap<String, Message>,
+    /// Operations of all port types, in declaration order.
+    pub operations: Vec<Operation>,
+    pub port_types: BTreeMap<String, PortType>,
+    pub bindings: BTreeMap<String, Binding>,
+    pub services: Vec<Service>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SimpleType {
+    Base64Binary,
+    Boolean,
+    String,
+    Float,
+    Int,
+    DateTime,
+    Complex(QualifiedTypename),
+}
+
+#[derive(Debug, Clone)]
+pub enum Occurence {
+    Unbounded,
+    Num(u32),
+}
+
+/// Whether local elements are namespace qualified (`elementFormDefault` and `form`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Form {
+    #[default]
+    Unqualified,
+    Qualified,
+}
+
+impl Form {
+    fn parse(s: Option<&String>) -> Option<Self> {
+        match s.map(|s| s.as_str()) {
+            Some("qualified") => Some(Form::Qualified),
+            Some("unqualified") => Some(Form::Unqualified),
+            _ => None,
+        }
+    }
+}
+
+/// A `<schema>` of the WSDL types.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    pub target_namespace: String,
+    pub element_form_default: Form,
+    /// Namespaces imported with `<import namespace="..."/>`.
+    pub imports: HashSet<String>,
+    /// Global element declarations.
+    pub elements: Vec<ElementDeclaration>,
+    /// Documentation of the types declared by the schema, by name.
+    pub documentation: BTreeMap<String, String>,
+}
+
+/// A global `<element name="..."/>` declaration.
+#[derive(Debug, Clone)]
+pub struct ElementDeclaration {
+    pub name: QualifiedTypename,
+    /// Type of the element, inline types are generated under the element name.
+    pub element_type: SimpleType,
+    /// Head of the substitution group the element belongs to.
+    pub substitution_group: Option<QualifiedTypename>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TypeAttribute {
+    pub nillable: bool,
+    pub min_occurs: Option<Occurence>,
+    pub max_occurs: Option<Occurence>,
+    /// Namespace the element is qualified with, `None` for unqualified elements.
+    pub namespace: Option<String>,
+    /// Global element referenced with `<element ref="..."/>`.
+    pub reference: Option<QualifiedTypename>,
+    /// Elements which may substitute the referenced one, with their types.
+    pub substitutions: Vec<(QualifiedTypename, SimpleType)>,
+    /// Value of an absent or empty element or attribute (`default="..."`).
+    pub default: Option<String>,
+    /// Only value the element or attribute may have (`fixed="..."`).
+    pub fixed: Option<String>,
+    pub documentation: Option<String>,
+}
+
+impl TypeAttribute {
+    /// Minimum number of occurrences, `minOccurs` defaults to 1.
+    pub fn min(&self) -> u32 {
+        match self.min_occurs {
+            None => 1,
+            Some(Occurence::Num(n)) => n,
+            Some(Occurence::Unbounded) => 0,
+        }
+    }
+
+    /// Maximum number of occurrences, `None` when unbounded. `maxOccurs` defaults to 1.
+    pub fn max(&self) -> Option<u32> {
+        match self.max_occurs {
+            None => Some(1),
+            Some(Occurence::Num(n)) => Some(n),
+            Some(Occurence::Unbounded) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ComplexType {
+    /// Fields in declaration order, which is the order of the serialized
+    /// elements. The fields inherited from `base` come first.
+    pub fields: Vec<(String, (TypeAttribute, SimpleType))>,
+    /// Declared `<attribute/>`s, required ones having a `min()` of 1.
+    pub attributes: Vec<(String, (TypeAttribute, SimpleType))>,
+    /// Type extended with `<complexContent><extension base="..."/>`.
+    pub base: Option<QualifiedTypename>,
+    /// Declared `abstract="true"`, only derived types may occur.
+    pub is_abstract: bool,
+    /// Declared `mixed="true"`, text may occur between the elements.
+    pub mixed: bool,
+    /// The sequence accepts arbitrary elements (`<any/>`).
+    pub any: bool,
+    /// The type accepts arbitrary attributes (`<anyAttribute/>`).
+    pub any_attribute: bool,
+}
+
+/// A fully qualified type name, consisting of a namespace and type name
+#[derive(Debug, Hash, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct QualifiedTypename(String, String);
+
+impl QualifiedTypename {
+    pub fn new(namespace: &str, name: &str) -> Self {
+        QualifiedTypename(namespace.to_string(), name.to_string())
+    }
+
+    fn as_tuple(&self) -> (&str, &str) {
+        (&self.0, &self.1)
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.0
+    }
+
+    pub fn name(&self) -> &str {
+        &self.1
+    }
+}
+
+impl FromStr for QualifiedTypename {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+
+        let namespace = parts.next().ok_or("invalid qualified name")?.to_owned();
+        let name = parts.next().ok_or("invalid qualified name")?.to_owned();
+        Ok(Self(namespace, name))
+    }
+}
+
+impl std::fmt::Display for QualifiedTypename {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}:{}", self.0, self.1))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Type {
+    Simple(SimpleType),
+    Complex(ComplexType),
+    /// SOAP-encoded array (a `soapenc:Array` restriction) of the given item type.
+    Array(SimpleType),
+    /// Whitespace separated list (`<list itemType="..."/>`) of the given item type.
+    List(SimpleType),
+    /// Value of the first of the member types (`<union memberTypes="..."/>`) it is valid for.
+    Union(Vec<SimpleType>),
+    Import(String),
+}
+
+/// Abstract message (`<message>`).
+#[derive(Debug, Clone)]
+pub struct Message {
+    /// Parts in declaration order.
+    pub parts: Vec<Part>,
+    pub content: MessageContent,
+}
+
+/// Part of a message (`<part>`).
+#[derive(Debug, Clone)]
+pub enum Part {
+    /// A global element (`element="..."`), written as is.
+    Element {
+        name: String,
+        element: QualifiedTypename,
+    },
+    /// A value of a type (`type="..."`), written as an unqualified element
+    /// named after the part, like the parameters of RPC operations.
+    Type { name: String, part_type: SimpleType },
+}
+
+/// What a message is generated as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageContent {
+    /// A single element part, like the messages of document style
+    /// operations: the message wraps the type of the element.
+    Element(QualifiedTypename),
+    /// Any other parts: the message is the given complex type, with a field
+    /// per part, see `message_parts_type`.
+    Parts(QualifiedTypename),
+}
+
+impl Message {
+    /// The element of a message made of a single element part.
+    pub fn element(&self) -> Option<&QualifiedTypename> {
+        match &self.content {
+            MessageContent::Element(element) => Some(element),
+            MessageContent::Parts(_) => None,
+        }
+    }
+}
+
+/// Message exchange pattern of an operation, from the order of its input and
+/// output messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transmission {
+    /// The endpoint receives a message: input only.
+    OneWay,
+    /// The endpoint receives a message and replies: input, then output.
+    RequestResponse,
+    /// The endpoint sends a message and receives a reply: output, then input.
+    SolicitResponse,
+    /// The endpoint sends a message: output only.
+    Notification,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub name: String,
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub faults: Option<Vec<String>>,
+    /// `name` of the input, distinguishing overloaded operations.
+    pub input_name: Option<String>,
+    /// `name` of the output, distinguishing overloaded operations.
+    pub output_name: Option<String>,
+    pub transmission: Transmission,
+    pub documentation: Option<String>,
+}
+
+/// Named set of abstract operations (`<portType>`).
+#[derive(Debug, Clone)]
+pub struct PortType {
+    pub name: String,
+    /// Operations in declaration order, overloaded operations share a name.
+    pub operations: Vec<Operation>,
+    pub documentation: Option<String>,
+}
+
+/// Protocol details of a port type (`<binding>`).
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub name: String,
+    pub port_type: String,
+}
+
+/// Set of endpoints (`<service>`).
+#[derive(Debug, Clone)]
+pub struct Service {
+    pub name: String,
+    pub ports: Vec<Port>,
+    pub documentation: Option<String>,
+}
+
+/// Endpoint of a binding (`<port>`).
+#[derive(Debug, Clone)]
+pub struct Port {
+    pub name: String,
+    pub binding: String,
+    /// Address of the endpoint, from `<soap:address location="..."/>`.
+    pub location: Option<String>,
+}
+
+//FIXME: splitting the namespace is the naive way, we should keep the namespace
+// and check for collisions instead
+fn split_namespace(s: &str) -> &str {
+    match s.find(':') {
+        None => s,
+        Some(index) => &s[index + 1..],
+    }
+}
+
+fn qualified_type(s: &str, namespaces: &xmltree::Namespace, default_ns: &str) -> QualifiedTypename {
+    match s.find(':') {
+        None => QualifiedTypename(default_ns.to_owned(), s.to_owned()),
+        Some(index) => {
+            let ns = namespaces.get(&s[..index]).unwrap();
+            QualifiedTypename(ns.to_owned(), s[index + 1..].to_owned())
+        }
+    }
+}
+
+const SOAP_ENCODING_NS: &str = "http://schemas.xmlsoap.org/soap/encoding/";
+const WSDL20_NS: &str = "http://www.w3.org/ns/wsdl";
+
+fn parse_type_ref(name: &QualifiedTypename) -> SimpleType {
+    match name.as_tuple() {
+        ("http://www.w3.org/2001/XMLSchema", "boolean") => SimpleType::Boolean,
+        ("http://www.w3.org/2001/XMLSchema", "string") => SimpleType::String,
+        ("http://www.w3.org/2001/XMLSchema", "int") => SimpleType::Int,
+        ("http://www.w3.org/2001/XMLSchema", "float") => SimpleType::Float,
+        ("http://www.w3.org/2001/XMLSchema", "dateTime") => SimpleType::DateTime,
+        ("http://www.w3.org/2001/XMLSchema", "base64Binary") => SimpleType::Base64Binary,
+        ("http://www.w3.org/2001/XMLSchema", t) => {
+            warn!("unhandled simple type: {t}");
+
+            SimpleType::Complex(QualifiedTypename(
+                "http://www.w3.org/2001/XMLSchema".to_string(),
+                t.to_string(),
+            ))
+        }
+        (n, s) => SimpleType::Complex(QualifiedTypename(n.to_string(), s.to_string())),
+    }
+}
+
+/// Parses a local element declaration into its name, attributes and type.
+///
+/// `<element ref="..."/>` declarations refer to a global element, which is
+/// always qualified and whose type is generated under the element name.
+fn parse_element(
+    field: &Element,
+    schema: &Schema,
+) -> Result<(String, (TypeAttribute, SimpleType)), WsdlError> {
+    let (field_name, simple_type, namespace, reference) = match field.attributes.get("ref") {
+        Some(element_ref) => {
+            let element_ref = qualified_type(
+                element_ref,
+                field.namespaces.as_ref().unwrap(),
+                &schema.target_namespace,
+            );
+
+            (
+                element_ref.name().to_string(),
+                SimpleType::Complex(element_ref.clone()),
+                Some(element_ref.namespace().to_string()),
+                Some(element_ref),
+            )
+        }
+        None => {
+            let field_name = field
+                .attributes
+                .get("name")
+                .ok_or(WsdlError::AttributeNotFound("name"))?;
+            let field_type = field
+                .attributes
+                .get("type")
+                .ok_or(WsdlError::AttributeNotFound("type"))?;
+
+            trace!("field {:?} -> {:?}", field_name, field_type);
+            let simple_type = parse_type_ref(&qualified_type(
+                field_type.as_str(),
+                field.namespaces.as_ref().unwrap(),
+                &schema.target_namespace,
+            ));
+
+            let form =
+                Form::parse(field.attributes.get("form")).unwrap_or(schema.element_form_default);
+            let namespace = match form {
+                Form::Qualified => Some(schema.target_namespace.clone()),
+                Form::Unqualified => None,
+            };
+
+            (field_name.to_string(), simple_type, namespace, None)
+        }
+    };
+
+    let nillable = match field.attributes.get("nillable").map(|s| s.as_str()) {
+        Some("true") => true,
+        Some("false") => false,
+        _ => false,
+    };
+
+    let min_occurs = parse_occurence(field.attributes.get("minOccurs"));
+    let max_occurs = parse_occurence(field.attributes.get("maxOccurs"));
+
+    let type_attributes = TypeAttribute {
+        nillable,
+        min_occurs,
+        max_occurs,
+        namespace,
+        reference,
+        substitutions: Vec::new(),
+        default: field.attributes.get("default").cloned(),
+        fixed: field.attributes.get("fixed").cloned(),
+        documentation: parse_documentation(field),
+    };
+
+    Ok((field_name, (type_attributes, simple_type)))
+}
+
+fn parse_occurence(s: Option<&String>) -> Option<Occurence> {
+    match s.map(|s| s.as_str()) {
+        None => None,
+        Some("unbounded") => Some(Occurence::Unbounded),
+        Some(n) => match n.parse() {
+            Ok(n) => Some(Occurence::Num(n)),
+            Err(_) => {
+                warn!("invalid occurence {n}");
+                None
+            }
+        },
+    }
+}
+
+/// Text of the `<documentation>` of a WSDL element, or of the
+/// `<annotation><documentation>` of a schema component. Lines are trimmed of
+/// their indentation, and the text of several elements is separated by a
+/// blank line.
+fn parse_documentation(el: &Element) -> Option<String> {
+    fn children<'a>(el: &'a Element, name: &'static str) -> Vec<&'a Element> {
+        el.children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| c.name == name)
+            .collect()
+    }
+
+    let mut paragraphs = vec![];
+    for documentation in children(el, "documentation").into_iter().chain(
+        children(el, "annotation")
+            .into_iter()
+            .flat_map(|a| children(a, "documentation")),
+    ) {
+        let Some(text) = documentation.get_text() else {
+            continue;
+        };
+        let lines = text.lines().map(|l| l.trim()).collect::<Vec<_>>();
+        let start = lines.iter().position(|l| !l.is_empty());
+        let end = lines.iter().rposition(|l| !l.is_empty());
+        if let (Some(start), Some(end)) = (start, end) {
+            paragraphs.push(lines[start..=end].join("\n"));
+        }
+    }
+
+    (!paragraphs.is_empty()).then(|| paragraphs.join("\n\n"))
+}
+
+/// Reference: https://learn.microsoft.com/en-us/previous-versions/dotnet/netframework-4.0/ms256050(v=vs.100)
+///
+/// Anonymous lists and unions nested in the type named `name` are added to
+/// `types`, see `parse_inline_simple_types`.
+fn parse_simple_type(
+    el: &Element,
+    name: &QualifiedTypename,
+    types: &mut BTreeMap<QualifiedTypename, Type>,
+) -> Result<Type, WsdlError> {
+    let target_namespace = name.namespace();
+
+    // Simply type inner may only be one of:
+    // - restriction
+    // - list
+    // - union
+
+    // <s:simpleType name="guid">
+    //   <s:restriction base="s:string">
+    //     <s:pattern value="[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}" />
+    //   </s:restriction>
+    // </s:simpleType>
+
+    let inner = el
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .find(|c| c.name != "annotation")
+        .ok_or(WsdlError::Empty)?;
+    let namespaces = el.namespaces.as_ref().unwrap();
+
+    match inner.name.as_str() {
+        "restriction" => {
+            // Extends a base type with certain restrictions.
+            for _child in inner.children.iter().filter_map(|c| c.as_element()) {
+                // https://learn.microsoft.com/en-us/previous-versions/dotnet/netframework-4.0/ms256057(v=vs.100)
+                // TODO: Parse these...
+            }
+
+            let base_type = inner
+                .attributes
+                .get("base")
+                .ok_or(WsdlError::AttributeNotFound("base"))?;
+
+            Ok(Type::Simple(parse_type_ref(&qualified_type(
+                base_type,
+                namespaces,
+                target_namespace,
+            ))))
+        }
+        // <s:list itemType="s:int"/>
+        "list" => {
+            let item_type = match inner.attributes.get("itemType") {
+                Some(item_type) => {
+                    parse_type_ref(&qualified_type(item_type, namespaces, target_namespace))
+                }
+                None => parse_inline_simple_types(inner, name, "Item", 0, types)?
+                    .pop()
+                    .ok_or(WsdlError::AttributeNotFound("itemType"))?,
+            };
+
+            Ok(Type::List(item_type))
+        }
+        // <s:union memberTypes="s:int tns:Keyword"/>
+        "union" => {
+            let mut member_types = inner
+                .attributes
+                .get("memberTypes")
+                .map(|members| {
+                    members
+                        .split_whitespace()
+                        .map(|m| parse_type_ref(&qualified_type(m, namespaces, target_namespace)))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let inline_types =
+                parse_inline_simple_types(inner, name, "Member", member_types.len(), types)?;
+            member_types.extend(inline_types);
+
+            if member_types.is_empty() {
+                return Err(WsdlError::AttributeNotFound("memberTypes"));
+            }
+            Ok(Type::Union(member_types))
+        }
+        n => {
+            warn!("unhandled simpleType inner: {n}");
+            Err(WsdlError::ElementNotFound("restriction"))
+        }
+    }
+}
+
+/// Parses the anonymous `<simpleType>` children of a list or union named
+/// `name`.
+///
+/// Restrictions are replaced with their base type. Nested lists and unions
+/// are added to `types`, named after `name`: `{name}Item` for the item of a
+/// list, `{name}Member{n}` for the `n`th member of a union, counting the
+/// `preceding` members of its `memberTypes`.
+fn parse_inline_simple_types(
+    el: &Element,
+    name: &QualifiedTypename,
+    kind: &str,
+    preceding: usize,
+    types: &mut BTreeMap<QualifiedTypename, Type>,
+) -> Result<Vec<SimpleType>, WsdlError> {
+    el.children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| c.name == "simpleType")
+        .enumerate()
+        .map(|(i, c)| {
+            let inline_name = match kind {
+                "Member" => format!("{}Member{}", name.name(), preceding + i + 1),
+                kind => format!("{}{kind}", name.name()),
+            };
+            let inline_name = QualifiedTypename::new(name.namespace(), &inline_name);
+
+            match parse_simple_type(c, &inline_name, types)? {
+                Type::Simple(t) => Ok(t),
+                t => {
+                    types.insert(inline_name.clone(), t);
+                    Ok(SimpleType::Complex(inline_name))
+                }
+            }
+        })
+        .collect()
+}
+
+/// Parses the item type of a SOAP-encoded array restriction.
+///
+/// ```xml
+/// <complexContent>
+///   <restriction base="soapenc:Array">
+///     <attribute ref="soapenc:arrayType" wsdl:arrayType="xsd:string[]"/>
+///   </restriction>
+/// </complexContent>
+/// ```
+///
+/// Some toolkits declare the item as a sequence instead of (or on top of) the
+/// `arrayType` attribute, so both forms are accepted.
+fn parse_array_type(restriction: &Element, schema: &Schema) -> Result<SimpleType, WsdlError> {
+    for child in restriction.children.iter().filter_map(|c| c.as_element()) {
+        match child.name.as_str() {
+            "attribute" => {
+                if let Some(array_type) = child.attributes.get("arrayType") {
+                    // strip the dimensions, `xsd:string[]` or `xsd:string[3]`
+                    let item_type = match array_type.find('[') {
+                        Some(index) => &array_type[..index],
+                        None => array_type.as_str(),
+                    };
+
+                    return Ok(parse_type_ref(&qualified_type(
+                        item_type,
+                        child.namespaces.as_ref().unwrap(),
+                        &schema.target_namespace,
+                    )));
+                }
+            }
+            "sequence" => {
+                if let Some(item) = child.children.iter().filter_map(|c| c.as_element()).next() {
+                    let (_, (_, item_type)) = parse_element(item, schema)?;
+                    return Ok(item_type);
+                }
+            }
+            n => trace!("unhandled soapenc:Array restriction inner: {n}"),
+        }
+    }
+
+    Err(WsdlError::AttributeNotFound("arrayType"))
+}
+
+/// Reference: https://learn.microsoft.com/en-us/previous-versions/dotnet/netframework-4.0/ms256067(v=vs.100)
+fn parse_complex_type(el: &Element, schema: &Schema) -> Result<Type, WsdlError> {
+    let mut complex_type = ComplexType {
+        fields: Vec::new(),
+        attributes: Vec::new(),
+        any: false,
+        any_attribute: false,
+        base: None,
+        is_abstract: el.attributes.get("abstract").map(|s| s.as_str()) == Some("true"),
+        mixed: el.attributes.get("mixed").map(|s| s.as_str()) == Some("true"),
+    };
+
+    for child in el.children.iter() {
+        let child = child.as_element().ok_or(WsdlError::NotAnElement)?;
+
+        match child.name.as_str() {
+            "complexContent" => {
+                if child.attributes.get("mixed").map(|s| s.as_str()) == Some("true") {
+                    complex_type.mixed = true;
+                }
+
+                let derivation = child
+                    .get_child("extension")
+                    .or_else(|| child.get_child("restriction"))
+                    .ok_or(WsdlError::ElementNotFound("restriction"))?;
+                let base = derivation
+                    .attributes
+                    .get("base")
+                    .ok_or(WsdlError::AttributeNotFound("base"))?;
+                let base = qualified_type(
+                    base,
+                    derivation.namespaces.as_ref().unwrap(),
+                    &schema.target_namespace,
+                );
+
+                if base.as_tuple() == (SOAP_ENCODING_NS, "Array") {
+                    return Ok(Type::Array(parse_array_type(derivation, schema)?));
+                }
+
+                if derivation.name != "extension" {
+                    trace!("unhandled complexContent restriction base: {base}");
+                    continue;
+                }
+
+                // <extension base="tns:Base"><sequence>...</sequence></extension>
+                for content in derivation.children.iter().filter_map(|c| c.as_element()) {
+                    parse_content(content, schema, &mut complex_type)?;
+                }
+                complex_type.base = Some(base);
+            }
+            _ => parse_content(child, schema, &mut complex_type)?,
+        }
+    }
+
+    Ok(Type::Complex(complex_type))
+}
+
+/// Parses the content model (`<sequence>`, `<anyAttribute>`, ...) of a complex type.
+fn parse_content(
+    child: &Element,
+    schema: &Schema,
+    complex_type: &mut ComplexType,
+) -> Result<(), WsdlError> {
+    match child.name.as_str() {
+        "sequence" | "choice" => {
+            // a choice is approximated with optional fields, repeated if the choice is
+            let choice = child.name == "choice";
+            let choice_max_occurs = parse_occurence(child.attributes.get("maxOccurs"));
+
+            for field in child.children.iter().filter_map(|c| c.as_element()) {
+                match field.name.as_str() {
+                    "element" => {}
+                    "any" => {
+                        complex_type.any = true;
+                        continue;
+                    }
+                    "sequence" | "choice" => {
+                        parse_content(field, schema, complex_type)?;
+                        continue;
+                    }
+                    n => {
+                        trace!("unhandled sequence inner: {n}");
+                        continue;
+                    }
+                }
+
+                let (field_name, mut field) = parse_element(field, schema)?;
+                if choice {
+                    field.0.min_occurs = Some(Occurence::Num(0));
+                    if field.0.max_occurs.is_none() {
+                        field.0.max_occurs = choice_max_occurs.clone();
+                    }
+                }
+
+                if complex_type
+                    .fields
+                    .iter()
+                    .any(|(name, _)| *name == field_name)
+                {
+                    warn!("duplicate field {field_name}, keeping the first declaration");
+                    continue;
+                }
+                complex_type.fields.push((field_name, field));
+            }
+        }
+        "attribute" => {
+            if let Some(attribute) = parse_attribute(child, schema)? {
+                complex_type.attributes.push(attribute);
+            }
+        }
+        "anyAttribute" => complex_type.any_attribute = true,
+        n => trace!("unhandled complexType inner: {n}"),
+    }
+
+    Ok(())
+}
+
+/// A named element or attribute declaration of a complex type.
+type Declaration = (String, (TypeAttribute, SimpleType));
+
+/// Parses an `<attribute name="..." type="..." use="..."/>` declaration.
+///
+/// Attributes are unqualified, and of type `string` when declared with an
+/// inline simple type.
+fn parse_attribute(attribute: &Element, schema: &Schema) -> Result<Option<Declaration>, WsdlError> {
+    if let Some(attribute_ref) = attribute.attributes.get("ref") {
+        trace!("unhandled attribute reference: {attribute_ref}");
+        return Ok(None);
+    }
+
+    let name = attribute
+        .attributes
+        .get("name")
+        .ok_or(WsdlError::AttributeNotFound("name"))?;
+    let attribute_type = match attribute.attributes.get("type") {
+        Some(t) => parse_type_ref(&qualified_type(
+            t,
+            attribute.namespaces.as_ref().unwrap(),
+            &schema.target_namespace,
+        )),
+        None => SimpleType::String,
+    };
+    let min_occurs = match attribute.attributes.get("use").map(|s| s.as_str()) {
+        Some("prohibited") => return Ok(None),
+        Some("required") => 1,
+        _ => 0,
+    };
+
+    let type_attributes = TypeAttribute {
+        min_occurs: Some(Occurence::Num(min_occurs)),
+        default: attribute.attributes.get("default").cloned(),
+        fixed: attribute.attributes.get("fixed").cloned(),
+        documentation: parse_documentation(attribute),
+        ..Default::default()
+    };
+
+    Ok(Some((name.to_string(), (type_attributes, attribute_type))))
+}
+
+/// Prepends the fields of the base types to the fields of derived types.
+fn inherit_fields(types: &mut BTreeMap<QualifiedTypename, Type>) {
+    let mut inherited = Vec::new();
+    for (name, t) in types.iter() {
+        let Type::Complex(c) = t else { continue };
+
+        let mut fields = Vec::new();
+        let mut attributes = Vec::new();
+        let mut any = c.any;
+        let mut any_attribute = c.any_attribute;
+        let mut visited = vec![name];
+        let mut base = c.base.as_ref();
+        while let Some(base_name) = base.filter(|b| !visited.contains(b)) {
+            let Some(Type::Complex(b)) = types.get(base_name) else {
+                warn!("base type {base_name} of {name} not found");
+                break;
+            };
+
+            fields.splice(0..0, b.fields.iter().cloned());
+            attributes.splice(0..0, b.attributes.iter().cloned());
+            any |= b.any;
+            any_attribute |= b.any_attribute;
+            visited.push(base_name);
+            base = b.base.as_ref();
+        }
+
+        if c.base.is_some() {
+            inherited.push((name.clone(), fields, attributes, any, any_attribute));
+        }
+    }
+
+    for (name, fields, attributes, any, any_attribute) in inherited {
+        if let Some(Type::Complex(c)) = types.get_mut(&name) {
+            for field in fields.into_iter().rev() {
+                if c.fields.iter().all(|(name, _)| *name != field.0) {
+                    c.fields.insert(0, field);
+                }
+            }
+            for attribute in attributes.into_iter().rev() {
+                if c.attributes.iter().all(|(name, _)| *name != attribute.0) {
+                    c.attributes.insert(0, attribute);
+                }
+            }
+            c.any = any;
+            c.any_attribute = any_attribute;
+        }
+    }
+}
+
+/// Resolves the type and the substitution group of `<element ref="..."/>` fields.
+fn resolve_references(schemas: &[Schema], types: &mut BTreeMap<QualifiedTypename, Type>) {
+    let elements = schemas
+        .iter()
+        .flat_map(|s| s.elements.iter())
+        .collect::<Vec<_>>();
+
+    // whether `element` belongs to the substitution group of `head`, directly or not
+    let substitutes = |element: &ElementDeclaration, head: &QualifiedTypename| {
+        let mut group = element.substitution_group.as_ref();
+        let mut visited = vec![&element.name];
+        while let Some(g) = group.filter(|g| !visited.contains(g)) {
+            if g == head {
+                return true;
+            }
+            visited.push(g);
+            group = elements
+                .iter()
+                .find(|e| e.name == *g)
+                .and_then(|e| e.substitution_group.as_ref());
+        }
+        false
+    };
+
+    for t in types.values_mut() {
+        let Type::Complex(c) = t else { continue };
+
+        for (_, (attributes, field_type)) in c.fields.iter_mut() {
+            let Some(reference) = attributes.reference.as_ref() else {
+                continue;
+            };
+
+            if let Some(element) = elements.iter().find(|e| e.name == *reference) {
+                *field_type = element.element_type.clone();
+            }
+            attributes.substitutions = elements
+                .iter()
+                .filter(|e| substitutes(e, reference))
+                .map(|e| (e.name.clone(), e.element_type.clone()))
+                .collect();
+        }
+    }
+}
+
+fn parse_schema(
+    schema_el: &Element,
+    schema: &mut Schema,
+) -> Result<BTreeMap<QualifiedTypename, Type>, WsdlError> {
+    let mut types = BTreeMap::new();
+
+    // Now parse individual types.
+    let elems = schema_el.children.iter().filter_map(|c| c.as_element());
+    for elem in elems {
+        trace!("type: {:#?}", elem);
+        let inner_type = match elem.name.as_str() {
+            // sometimes we have <element name="TypeName"><complexType>...</complexType></element>,
+            // sometimes we have <complexType name="TypeName">...</complexType>
+            "element" => {
+                let name = elem
+                    .attributes
+                    .get("name")
+                    .ok_or(WsdlError::AttributeNotFound("name"))?;
+                let name = QualifiedTypename(schema.target_namespace.clone(), name.to_string());
+                let namespaces = elem.namespaces.as_ref().unwrap();
+                let element_type = match elem.attributes.get("type") {
+                    Some(t) => {
+                        parse_type_ref(&qualified_type(t, namespaces, &schema.target_namespace))
+                    }
+                    None => SimpleType::Complex(name.clone()),
+                };
+                let substitution_group = elem
+                    .attributes
+                    .get("substitutionGroup")
+                    .map(|g| qualified_type(g, namespaces, &schema.target_namespace));
+
+                schema.elements.push(ElementDeclaration {
+                    name,
+                    element_type,
+                    substitution_group,
+                });
+
+                // <element name="..." type="..."/> does not declare a new type
+                match elem
+                    .children
+                    .iter()
+                    .filter_map(|c| c.as_element())
+                    .find(|c| c.name != "annotation")
+                {
+                    Some(inner_type) => inner_type,
+                    None if elem.attributes.contains_key("type") => continue,
+                    None => return Err(WsdlError::Empty),
+                }
+            }
+            "complexType" => elem,
+            "simpleType" => elem,
+            "annotation" => continue,
+            // ```
+            // <s:schema elementFormDefault="qualified" targetNamespace="http://www.microsoft.com/SoftwareDistribution">
+            //   <s:import namespace="http://microsoft.com/wsdl/types/" />
+            //   // ..... types that may refer to other namespace
+            // </s:schema>
+            // ```
+            "import" => {
+                let tns = elem
+                    .attributes
+                    .get("namespace")
+                    .ok_or(WsdlError::AttributeNotFound("namespace"))?;
+
+                schema.imports.insert(tns.clone());
+                continue;
+            }
+            n => {
+                warn!("skipping unsupported schema component {n}");
+                continue;
+            }
+        };
+
+        let name = elem
+            .attributes
+            .get("name")
+            .ok_or(WsdlError::AttributeNotFound("name"))?;
+
+        if let Some(documentation) =
+            parse_documentation(elem).or_else(|| parse_documentation(inner_type))
+        {
+            schema.documentation.insert(name.to_string(), documentation);
+        }
+
+        let new_type = match inner_type.name.as_str() {
+            "complexType" => parse_complex_type(inner_type, schema)?,
+            "simpleType" => parse_simple_type(
+                inner_type,
+                &QualifiedTypename(schema.target_namespace.clone(), name.to_string()),
+                &mut types,
+            )?,
+            n => {
+                warn!("skipping {name}, unsupported type {n}");
+                continue;
+            }
+        };
+
+        types.insert(
+            QualifiedTypename(schema.target_namespace.clone(), name.to_string()),
+            new_type,
+        );
+    }
+
+    Ok(types)
+}
+
+pub fn parse_types(
+    root_el: &Element,
+    target_namespace: &str,
+) -> Result<(Vec<Schema>, BTreeMap<QualifiedTypename, Type>), WsdlError> {
+    parse_schemas(
+        root_el.children.iter().filter_map(|c| c.as_element()),
+        target_namespace,
+    )
+}
+
+/// Parses `<schema>` elements, schemas without a `targetNamespace` being in
+/// `target_namespace`.
+pub(crate) fn parse_schemas<'a>(
+    schema_els: impl IntoIterator<Item = &'a Element>,
+    target_namespace: &str,
+) -> Result<(Vec<Schema>, BTreeMap<QualifiedTypename, Type>), WsdlError> {
+    let mut schemas = Vec::new();
+    let mut types = BTreeMap::new();
+
+    for schema_el in schema_els {
+        let mut schema = Schema {
+            target_namespace: schema_el
+                .attributes
+                .get("targetNamespace")
+                .map(|ns| ns.as_str())
+                .unwrap_or(target_namespace)
+                .to_string(),
+            element_form_default: Form::parse(schema_el.attributes.get("elementFormDefault"))
+                .unwrap_or_default(),
+            imports: HashSet::new(),
+            elements: Vec::new(),
+            documentation: BTreeMap::new(),
+        };
+
+        // HACK: Ignoring imports for now and just flattening the namespaces.
+        let new_types = parse_schema(schema_el, &mut schema)?;
+
+        types.extend(new_types);
+        schemas.push(schema);
+    }
+
+    inherit_fields(&mut types);
+    resolve_references(&schemas, &mut types);
+
+    Ok((schemas, types))
+}
+
+/// Registers the complex type of a message which is not made of a single
+/// element part, named after the message, with a field per part: element
+/// parts are fields named after their element, type parts are unqualified
+/// fields named after the part, like the accessors of RPC operations.
+fn message_parts_type(
+    message_name: &str,
+    parts: &[Part],
+    namespace: &str,
+    schemas: &[Schema],
+    types: &mut BTreeMap<QualifiedTypename, Type>,
+) -> QualifiedTypename {
+    let fields = parts
+        .iter()
+        .map(|part| match part {
+            Part::Element { element, .. } => {
+                let element_type = schemas
+                    .iter()
+                    .flat_map(|schema| schema.elements.iter())
+                    .find(|e| e.name == *element)
+                    .map(|e| e.element_type.clone())
+                    .unwrap_or_else(|| SimpleType::Complex(element.clone()));
+                let attributes = TypeAttribute {
+                    namespace: Some(element.namespace().to_string()),
+                    reference: Some(element.clone()),
+                    ..Default::default()
+                };
+                (element.name().to_string(), (attributes, element_type))
+            }
+            Part::Type { name, part_type } => {
+                (name.clone(), (TypeAttribute::default(), part_type.clone()))
+            }
+        })
+        .collect();
+
+    // messages and types are distinct symbol spaces, a type may have the name
+    let mut name = QualifiedTypename::new(namespace, message_name);
+    while types.contains_key(&name) {
+        name = QualifiedTypename::new(namespace, &format!("{}Parts", name.name()));
+    }
+    types.insert(
+        name.clone(),
+        Type::Complex(ComplexType {
+            fields,
+            attributes: vec![],
+            base: None,
+            is_abstract: false,
+            mixed: false,
+            any: false,
+            any_attribute: false,
+        }),
+    );
+    name
+}
+
+pub fn parse(bytes: &[u8]) -> Result<Wsdl, WsdlError> {
+    let mut messages = BTreeMap::new();
+    let mut operations: Vec<Operation> = Vec::new();
+    let mut target_namespace = Vec::new();
+
+    let elements = Element::parse(bytes)?;
+    trace!("elements: {:#?}", elements);
+    target_namespace.push(
+        elements
+            .attributes
+            .get("targetNamespace")
+            .ok_or(WsdlError::AttributeNotFound("targetNamespace"))?
+            .to_string(),
+    );
+
+    let types_el = elements
+        .get_child("types")
+        .ok_or(WsdlError::ElementNotFound("types"))?;
+
+    let default_ns = target_namespace.last().unwrap();
+    let (schemas, mut types) = parse_types(types_el, default_ns)?;
+
+    if elements.name == "description" && elements.namespace.as_deref() == Some(WSDL20_NS) {
+        return parse_description(&elements, default_ns, schemas, types);
+    }
+
+    for message in elements
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| c.name == "message")
+    {
+        trace!("message: {:#?}", message);
+        let name = message
+            .attributes
+            .get("name")
+            .ok_or(WsdlError::AttributeNotFound("name"))?;
+
+        let mut parts = vec![];
+        for part in message
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| c.name == "part")
+        {
+            let part_name = part
+                .attributes
+                .get("name")
+                .ok_or(WsdlError::AttributeNotFound("name"))?
+                .to_string();
+            let namespaces = part.namespaces.as_ref().unwrap();
+            parts.push(
+                match (part.attributes.get("element"), part.attributes.get("type")) {
+                    (Some(element), _) => Part::Element {
+                        name: part_name,
+                        element: qualified_type(element, namespaces, default_ns),
+                    },
+                    (None, Some(part_type)) => Part::Type {
+                        name: part_name,
+                        part_type: parse_type_ref(&qualified_type(
+                            part_type, namespaces, default_ns,
+                        )),
+                    },
+                    (None, None) => return Err(WsdlError::AttributeNotFound("element")),
+                },
+            );
+        }
+
+        let content = match parts.as_slice() {
+            [Part::Element { element, .. }] => MessageContent::Element(element.clone()),
+            _ => MessageContent::Parts(message_parts_type(
+                name, &parts, default_ns, &schemas, &mut types,
+            )),
+        };
+        messages.insert(name.to_string(), Message { parts, content });
+    }
+
+    let mut port_types = BTreeMap::new();
+    let mut bindings = BTreeMap::new();
+    let mut services = Vec::new();
+    for child in elements.children.iter().filter_map(|c| c.as_element()) {
+        match child.name.as_str() {
+            "portType" => {
+                let port_type = parse_port_type(child)?;
+                for operation in port_type.operations.iter() {
+                    if !operations.contains(operation) {
+                        operations.push(operation.clone());
+                    }
+                }
+                port_types.insert(port_type.name.clone(), port_type);
+            }
+            "binding" => {
+                let name = child
+                    .attributes
+                    .get("name")
+                    .ok_or(WsdlError::AttributeNotFound("name"))?;
+                let port_type = child
+                    .attributes
+                    .get("type")
+                    .ok_or(WsdlError::AttributeNotFound("type"))?;
+
+                bindings.insert(
+                    name.to_string(),
+                    Binding {
+                        name: name.to_string(),
+                        port_type: split_namespace(port_type).to_string(),
+                    },
+                );
+            }
+            "service" => services.push(parse_service(child)?),
+            _ => {}
+        }
+    }
+
+    if port_types.is_empty() {
+        return Err(WsdlError::ElementNotFound("portType"));
+    }
+    let service_name = &services
+        .first()
+        .ok_or(WsdlError::ElementNotFound("service"))?
+        .name;
+
+    debug!("service name: {}", service_name);
+    debug!("parsed types: {:#?}", types);
+    debug!("parsed messages: {:#?}", messages);
+    debug!("parsed operations: {:#?}", operations);
+
+    Ok(Wsdl {
+        name: service_name.to_string(),
+        target_namespace: target_namespace.last().unwrap().clone(),
+        schemas,
+        types,
+        messages,
+        operations,
+        port_types,
+        bindings,
+        services,
+    })
+}
+
+fn parse_port_type(port_type: &Element) -> Result<PortType, WsdlError> {
+    let mut operations = Vec::new();
+
+    for operation in port_type
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| c.name == "operation")
+    {
+        let operation_name = operation
+            .attributes
+            .get("name")
+            .ok_or(WsdlError::AttributeNotFound("name"))?;
+
+        let mut input = None;
+        let mut output = None;
+        let mut faults = None;
+        let mut input_name = None;
+        let mut output_name = None;
+        let mut input_first = None;
+        for child in operation
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| c.attributes.contains_key("message"))
+        {
+            let message = split_namespace(
+                child
+                    .attributes
+                    .get("message")
+                    .ok_or(WsdlError::AttributeNotFound("message"))?,
+            );
+
+            // FIXME: not testing for unicity
+            match child.name.as_str() {
+                "input" => {
+                    input = Some(message.to_string());
+                    input_name = child.attributes.get("name").cloned();
+                    input_first.get_or_insert(true);
+                }
+                "output" => {
+                    output = Some(message.to_string());
+                    output_name = child.attributes.get("name").cloned();
+                    input_first.get_or_insert(false);
+                }
+                "fault" => {
+                    if faults.is_none() {
+                        faults = Some(Vec::new());
+                    }
+                    if let Some(v) = faults.as_mut() {
+                        v.push(message.to_string());
+                    }
+                }
+                _ => return Err(WsdlError::ElementNotFound("operation member")),
+            }
+        }
+
+        let transmission = match (input.is_some(), output.is_some(), input_first) {
+            (true, false, _) => Transmission::OneWay,
+            (true, true, Some(true)) => Transmission::RequestResponse,
+            (true, true, _) => Transmission::SolicitResponse,
+            (false, true, _) => Transmission::Notification,
+            (false, false, _) => return Err(WsdlError::ElementNotFound("input")),
+        };
+
+        operations.push(Operation {
+            name: operation_name.to_string(),
+            input,
+            output,
+            faults,
+            input_name,
+            output_name,
+            transmission,
+            documentation: parse_documentation(operation),
+        });
+    }
+
+    Ok(PortType {
+        name: port_type
+            .attributes
+            .get("name")
+            .ok_or(WsdlError::AttributeNotFound("name"))?
+            .to_string(),
+        operations,
+        documentation: parse_documentation(port_type),
+    })
+}
+
+fn parse_service(service: &Element) -> Result<Service, WsdlError> {
+    let mut ports = Vec::new();
+
+    for port in service
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .filter(|c| c.name == "port")
+    {
+        let name = port
+            .attributes
+            .get("name")
+            .ok_or(WsdlError::AttributeNotFound("name"))?;
+        let binding = port
+            .attributes
+            .get("binding")
+            .ok_or(WsdlError::AttributeNotFound("binding"))?;
+        // `soap:address` or `soap12:address`
+        let location = port
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .find(|c| c.name == "address")
+            .and_then(|address| address.attributes.get("location"))
+            .cloned();
+
+        ports.push(Port {
+            name: name.to_string(),
+            binding: split_namespace(binding).to_string(),
+            location,
+        });
+    }
+
+    Ok(Service {
+        name: service
+            .attributes
+            .get("name")
+            .ok_or(WsdlError::AttributeNotFound("name"))?
+            .to_string(),
+        ports,
+        documentation: parse_documentation(service),
+    })
+}
+
+/// Maps a WSDL 2.0 `<description>` to the WSDL 1.1 model.
+///
+/// Interfaces become port types and endpoints ports. WSDL 2.0 has no
+/// messages, so one is declared for each input, output and fault element,
+/// named `{Operation}Input`, `{Operation}Output` and `{Fault}Fault`.
+fn parse_description(
+    description: &Element,
+    target_namespace: &str,
+    schemas: Vec<Schema>,
+    types: BTreeMap<QualifiedTypename, Type>,
+) -> Result<Wsdl, WsdlError> {
+    let mut messages = BTreeMap::new();
+    let mut port_types = BTreeMap::new();
+    let mut bindings = BTreeMap::new();
+    let mut services = Vec::new();
+    // interfaces extended by each interface
+    let mut extends = Vec::new();
+
+    // declares the message of an element reference, `#any` and `#none` have none
+    let mut declare_message = |name: String, el: &Element| -> Result<Option<String>, WsdlError> {
+        let element = el
+            .attributes
+            .get("element")
+            .ok_or(WsdlError::AttributeNotFound("element"))?;
+        if element.starts_with('#') {
+            warn!("unhandled message content {element} of {name}");
+            return Ok(None);
+        }
+
+        let element = qualified_type(element, el.namespaces.as_ref().unwrap(), target_namespace);
+        messages.insert(
+            name.clone(),
+            Message {
+                parts: vec![Part::Element {
+                    name: "parameters".to_string(),
+                    element: element.clone(),
+                }],
+                content: MessageContent::Element(element),
+            },
+        );
+        Ok(Some(name))
+    };
+
+    for child in description.children.iter().filter_map(|c| c.as_element()) {
+        let name = child.attributes.get("name").map(|name| name.to_string());
+
+        match child.name.as_str() {
+            "interface" => {
+                let name = name.ok_or(WsdlError::AttributeNotFound("name"))?;
+                let mut operations = Vec::new();
+
+                for fault in child.children.iter().filter_map(|c| c.as_element()) {
+                    if fault.name != "fault" {
+                        continue;
+                    }
+                    let fault_name = fault
+                        .attributes
+                        .get("name")
+                        .ok_or(WsdlError::AttributeNotFound("name"))?;
+                    declare_message(format!("{fault_name}Fault"), fault)?;
+                }
+
+                for operation in child
+                    .children
+                    .iter()
+                    .filter_map(|c| c.as_element())
+                    .filter(|c| c.name == "operation")
+                {
+                    let operation_name = operation
+                        .attributes
+                        .get("name")
+                        .ok_or(WsdlError::AttributeNotFound("name"))?;
+                    let pattern = operation
+                        .attributes
+                        .get("pattern")
+                        .map(|p| p.as_str())
+                        .unwrap_or("http://www.w3.org/ns/wsdl/in-out");
+                    let transmission = match pattern.rsplit('/').next() {
+                        Some("in-only" | "robust-in-only") => Transmission::OneWay,
+                        Some("in-out" | "in-opt-out") => Transmission::RequestResponse,
+                        Some("out-in" | "out-opt-in") => Transmission::SolicitResponse,
+                        Some("out-only" | "robust-out-only") => Transmission::Notification,
+                        _ => {
+                            warn!(
+                                "unhandled message exchange pattern {pattern} of {operation_name}"
+                            );
+                            continue;
+                        }
+                    };
+
+                    let mut input = None;
+                    let mut output = None;
+                    let mut faults = None;
+                    for member in operation.children.iter().filter_map(|c| c.as_element()) {
+                        match member.name.as_str() {
+                            "input" => {
+                                input = declare_message(format!("{operation_name}Input"), member)?
+                            }
+                            "output" => {
+                                output = declare_message(format!("{operation_name}Output"), member)?
+                            }
+                            "infault" | "outfault" => {
+                                let fault = member
+                                    .attributes
+                                    .get("ref")
+                                    .ok_or(WsdlError::AttributeNotFound("ref"))?;
+                                faults
+                                    .get_or_insert_with(Vec::new)
+                                    .push(format!("{}Fault", split_namespace(fault)));
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    operations.push(Operation {
+                        name: operation_name.to_string(),
+                        input,
+                        output,
+                        faults,
+                        input_name: None,
+                        output_name: None,
+                        transmission,
+                        documentation: parse_documentation(operation),
+                    });
+                }
+
+                if let Some(extended) = child.attributes.get("extends") {
+                    extends.push((
+                        name.clone(),
+                        extended
+                            .split_whitespace()
+                            .map(|i| split_namespace(i).to_string())
+                            .collect::<Vec<_>>(),
+                    ));
+                }
+                port_types.insert(
+                    name.clone(),
+                    PortType {
+                        name,
+                        operations,
+                        documentation: parse_documentation(child),
+                    },
+                );
+            }
+            "binding" => {
+                let name = name.ok_or(WsdlError::AttributeNotFound("name"))?;
+                let interface = child
+                    .attributes
+                    .get("interface")
+                    .ok_or(WsdlError::AttributeNotFound("interface"))?;
+
+                bindings.insert(
+                    name.clone(),
+                    Binding {
+                        name,
+                        port_type: split_namespace(interface).to_string(),
+                    },
+                );
+            }
+            "service" => {
+                let mut ports = Vec::new();
+                for endpoint in child
+                    .children
+                    .iter()
+                    .filter_map(|c| c.as_element())
+                    .filter(|c| c.name == "endpoint")
+                {
+                    ports.push(Port {
+                        name: endpoint
+                            .attributes
+                            .get("name")
+                            .ok_or(WsdlError::AttributeNotFound("name"))?
+                            .to_string(),
+                        binding: split_namespace(
+                            endpoint
+                                .attributes
+                                .get("binding")
+                                .ok_or(WsdlError::AttributeNotFound("binding"))?,
+                        )
+                        .to_string(),
+                        location: endpoint.attributes.get("address").cloned(),
+                    });
+                }
+
+                services.push(Service {
+                    name: name.ok_or(WsdlError::AttributeNotFound("name"))?,
+                    ports,
+                    documentation: parse_documentation(child),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // operations of extended interfaces come first
+    for (name, extended) in extends {
+        let mut operations = extended
+            .iter()
+            .filter_map(|i| port_types.get(i))
+            .flat_map(|i| i.operations.iter().cloned())
+            .collect::<Vec<_>>();
+        let port_type = port_types.get_mut(&name).unwrap();
+        operations.append(&mut port_type.operations);
+        port_type.operations = operations;
+    }
+
+    if port_types.is_empty() {
+        return Err(WsdlError::ElementNotFound("interface"));
+    }
+    let service_name = services
+        .first()
+        .ok_or(WsdlError::ElementNotFound("service"))?
+        .name
+        .clone();
+
+    let mut operations: Vec<Operation> = Vec::new();
+    for operation in port_types.values().flat_map(|p| p.operations.iter()) {
+        if !operations.contains(operation) {
+            operations.push(operation.clone());
+        }
+    }
+
+    Ok(Wsdl {
+        name: service_name,
+        target_namespace: target_namespace.to_string(),
+        schemas,
+        types,
+        messages,
+        operations,
+        port_types,
+        bindings,
+        services,
+    })
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+mod tests {
+    use super::*;
+    const WIKIPEDIA_WSDL: &[u8] = include_bytes!("../../assets/wikipedia-example.wsdl");
+    const EXAMPLE_WSDL: &[u8] = include_bytes!("../../assets/example.wsdl");
+    const RPC_ENCODED_WSDL: &[u8] = include_bytes!("../../assets/rpc-encoded.wsdl");
+    const QUALIFIED_WSDL: &[u8] = include_bytes!("../../assets/qualified.wsdl");
+    const POLYMORPHIC_WSDL: &[u8] = include_bytes!("../../assets/polymorphic.wsdl");
+    const MIXED_WSDL: &[u8] = include_bytes!("../../assets/mixed.wsdl");
+    const DEFAULTS_WSDL: &[u8] = include_bytes!("../../assets/defaults.wsdl");
+    const LISTS_WSDL: &[u8] = include_bytes!("../../assets/lists.wsdl");
+    const MULTIPORT_WSDL: &[u8] = include_bytes!("../../assets/multiport.wsdl");
+    const OVERLOADED_WSDL: &[u8] = include_bytes!("../../assets/overloaded.wsdl");
+    const WSDL20_WSDL: &[u8] = include_bytes!("../../assets/wsdl20.wsdl");
+    const DOCUMENTED_WSDL: &[u8] = include_bytes!("../../assets/documented.wsdl");
+
+    #[test]
+    fn parse_example() {
+        let res = parse(EXAMPLE_WSDL);
+        println!("res: {:?}", res);
+        res.unwrap();
+    }
+
+    #[test]
+    fn parse_encoded_arrays() {
+        let wsdl = parse(RPC_ENCODED_WSDL).unwrap();
+
+        let spaces = &wsdl.types[&QualifiedTypename(
+            "urn:confluence".to_string(),
+            "ArrayOf_tns1_RemoteSpace".to_string(),
+        )];
+        assert!(matches!(spaces, Type::Array(SimpleType::Complex(t)) if t.name() == "RemoteSpace"));
+
+        let keys = &wsdl.types[&QualifiedTypename(
+            "urn:confluence".to_string(),
+            "ArrayOf_xsd_string".to_string(),
+        )];
+        assert!(matches!(keys, Type::Array(SimpleType::String)));
+    }
+
+    #[test]
+    fn parse_type_parts() {
+        let wsdl = parse(RPC_ENCODED_WSDL).unwrap();
+
+        // every part is kept, and the message is a type with a field per part
+        let request = &wsdl.messages["getPermissionsRequest"];
+        assert!(matches!(
+            request.parts.as_slice(),
+            [Part::Type { name: in0, part_type: SimpleType::String },
+             Part::Type { name: in1, part_type: SimpleType::String }]
+                if in0 == "in0" && in1 == "in1"
+        ));
+        let parts_type = QualifiedTypename::new("urn:confluence", "getPermissionsRequest");
+        assert_eq!(request.content, MessageContent::Parts(parts_type.clone()));
+        match &wsdl.types[&parts_type] {
+            Type::Complex(c) => {
+                let fields = c.fields.iter().map(|(name, (attributes, _))| {
+                    (name.as_str(), attributes.namespace.as_deref())
+                });
+                assert_eq!(fields.collect::<Vec<_>>(), [("in0", None), ("in1", None)]);
+            }
+            t => panic!("unexpected type {t:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_derived_types() {
+        let wsdl = parse(POLYMORPHIC_WSDL).unwrap();
+        let name = |name: &str| {
+            QualifiedTypename("http://tempuri.org/payments".to_string(), name.to_string())
+        };
+        let complex = |type_name: &str| match &wsdl.types[&name(type_name)] {
+            Type::Complex(c) => c,
+            t => panic!("unexpected type {t:?}"),
+        };
+        let field_names = |type_name: &str| {
+            complex(type_name)
+                .fields
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(complex("Payment").is_abstract);
+        assert_eq!(
+            complex("SepaTransferPayment").base,
+            Some(name("BankTransferPayment"))
+        );
+        assert_eq!(
+            field_names("SepaTransferPayment"),
+            vec!["Amount", "Iban", "Bic"]
+        );
+
+        let (attributes, field_type) = &complex("Checkout").fields[2].1;
+        assert_eq!(attributes.reference, Some(name("PaymentMethod")));
+        assert!(matches!(field_type, SimpleType::Complex(t) if *t == name("Payment")));
+        assert_eq!(
+            attributes
+                .substitutions
+                .iter()
+                .map(|(element, _)| element.name())
+                .collect::<Vec<_>>(),
+            vec!["CreditCard", "BankTransfer"]
+        );
+    }
+
+    #[test]
+    fn parse_mixed_content() {
+        let wsdl = parse(MIXED_WSDL).unwrap();
+        let Type::Complex(rich_text) = &wsdl.types[&QualifiedTypename(
+            "http://tempuri.org/documents".to_string(),
+            "RichText".to_string(),
+        )] else {
+            panic!("RichText should be a complex type");
+        };
+
+        assert!(rich_text.mixed);
+        // the members of a repeated choice are optional and repeated
+        assert_eq!(
+            rich_text
+                .fields
+                .iter()
+                .map(|(name, (attributes, _))| (name.as_str(), attributes.min(), attributes.max()))
+                .collect::<Vec<_>>(),
+            vec![("b", 0, None), ("link", 0, None)]
+        );
+    }
+
+    #[test]
+    fn parse_defaults_and_attributes() {
+        let wsdl = parse(DEFAULTS_WSDL).unwrap();
+        let Type::Complex(settings) = &wsdl.types[&QualifiedTypename(
+            "http://tempuri.org/settings".to_string(),
+            "Settings".to_string(),
+        )] else {
+            panic!("Settings should be a complex type");
+        };
+
+        let field = |name: &str| {
+            &settings
+                .fields
+                .iter()
+                .find(|(n, _)| n == name)
+                .unwrap()
+                .1
+                 .0
+        };
+        assert_eq!(field("PageSize").default.as_deref(), Some("25"));
+        assert_eq!(field("Schema").fixed.as_deref(), Some("v2"));
+        assert_eq!(field("Theme").default, None);
+
+        assert_eq!(
+            settings
+                .attributes
+                .iter()
+                .map(|(name, (attributes, _))| (
+                    name.as_str(),
+                    attributes.min(),
+                    attributes.default.as_deref(),
+                    attributes.fixed.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("version", 0, None, Some("1.0")),
+                ("id", 1, None, None),
+                ("scope", 0, Some("user"), None),
+                ("updated", 0, None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_lists_and_unions() {
+        let wsdl = parse(LISTS_WSDL).unwrap();
+        let ty = |name: &str| {
+            &wsdl.types
+                [&QualifiedTypename("http://tempuri.org/reports".to_string(), name.to_string())]
+        };
+
+        assert!(matches!(ty("Years"), Type::List(SimpleType::Int)));
+        // anonymous item types are replaced with their base type
+        assert!(matches!(ty("Tags"), Type::List(SimpleType::String)));
+        assert!(matches!(
+            ty("Limit"),
+            Type::Union(members) if matches!(
+                members.as_slice(),
+                [SimpleType::Int, SimpleType::Complex(keyword)] if keyword.name() == "Keyword"
+            )
+        ));
+        // member types come first, then anonymous ones, nested lists are named
+        assert!(matches!(
+            ty("Deadline"),
+            Type::Union(members) if matches!(
+                members.as_slice(),
+                [SimpleType::DateTime, SimpleType::Complex(list)] if list.name() == "DeadlineMember2"
+            )
+        ));
+        assert!(matches!(ty("DeadlineMember2"), Type::List(SimpleType::Int)));
+    }
+
+    #[test]
+    fn parse_services() {
+        let wsdl = parse(MULTIPORT_WSDL).unwrap();
+
+        assert_eq!(wsdl.name, "Inventory");
+        assert_eq!(
+            wsdl.port_types.keys().collect::<Vec<_>>(),
+            vec!["InventoryAdminSoap", "InventorySoap", "MonitoringSoap"]
+        );
+        assert_eq!(
+            wsdl.operations
+                .iter()
+                .map(|o| o.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["GetStock", "SetStock", "Ping"]
+        );
+        assert_eq!(wsdl.bindings["InventorySoap12"].port_type, "InventorySoap");
+
+        let services = wsdl
+            .services
+            .iter()
+            .map(|s| {
+                let ports = s
+                    .ports
+                    .iter()
+                    .map(|p| (p.name.as_str(), p.binding.as_str()));
+                (s.name.as_str(), ports.collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            services,
+            vec![
+                (
+                    "Inventory",
+                    vec![
+                        ("InventoryPublic", "InventorySoap"),
+                        ("InventoryPublic12", "InventorySoap12"),
+                        ("InventoryAdmin", "InventoryAdminSoap"),
+                    ]
+                ),
+                ("Monitoring", vec![("MonitoringSoap", "MonitoringSoap")]),
+            ]
+        );
+        assert_eq!(
+            wsdl.services[1].ports[0].location.as_deref(),
+            Some("http://localhost/monitoring.asmx")
+        );
+    }
+
+    #[test]
+    fn parse_overloaded_operations() {
+        let wsdl = parse(OVERLOADED_WSDL).unwrap();
+        let operations = wsdl.port_types["CatalogSoap"]
+            .operations
+            .iter()
+            .map(|o| (o.name.as_str(), o.input_name.as_deref(), o.transmission))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            operations,
+            vec![
+                (
+                    "Search",
+                    Some("SearchByName"),
+                    Transmission::RequestResponse
+                ),
+                ("Search", Some("SearchById"), Transmission::RequestResponse),
+                ("PriceChanged", None, Transmission::Notification),
+                (
+                    "PriceChangedWithConfirmation",
+                    None,
+                    Transmission::SolicitResponse
+                ),
+                ("Log", None, Transmission::OneWay),
+            ]
+        );
+        assert_eq!(wsdl.operations.len(), 5);
+    }
+
+    #[test]
+    fn parse_wsdl20() {
+        let wsdl = parse(WSDL20_WSDL).unwrap();
+
+        assert_eq!(wsdl.name, "Weather");
+        assert_eq!(
+            wsdl.bindings["WeatherSoapBinding"].port_type,
+            "WeatherInterface"
+        );
+        assert_eq!(
+            wsdl.services[0].ports[0].location.as_deref(),
+            Some("http://localhost/weather")
+        );
+
+        // operations of the extended interface come first
+        let operations = wsdl.port_types["WeatherInterface"]
+            .operations
+            .iter()
+            .map(|o| {
+                (
+                    o.name.as_str(),
+                    o.input.as_deref(),
+                    o.output.as_deref(),
+                    o.transmission,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operations,
+            vec![
+                (
+                    "SubmitReport",
+                    Some("SubmitReportInput"),
+                    None,
+                    Transmission::OneWay
+                ),
+                (
+                    "GetForecast",
+                    Some("GetForecastInput"),
+                    Some("GetForecastOutput"),
+                    Transmission::RequestResponse
+                ),
+                (
+                    "Alerts",
+                    None,
+                    Some("AlertsOutput"),
+                    Transmission::Notification
+                ),
+            ]
+        );
+        assert_eq!(
+            wsdl.port_types["WeatherInterface"].operations[1].faults,
+            Some(vec!["UnknownCityFault".to_string()])
+        );
+
+        assert_eq!(
+            wsdl.messages["GetForecastInput"].content,
+            MessageContent::Element(QualifiedTypename::new(
+                "http://tempuri.org/weather",
+                "GetForecast"
+            ))
+        );
+        assert_eq!(
+            wsdl.messages["UnknownCityFault"].element().unwrap().name(),
+            "UnknownCity"
+        );
+    }
+
+    #[test]
+    fn parse_documentation() {
+        let wsdl = parse(DOCUMENTED_WSDL).unwrap();
+
+        assert_eq!(
+            wsdl.schemas[0].documentation["Book"],
+            "A book of the catalog.\n\nBooks are identified by their ISBN."
+        );
+        assert_eq!(
+            wsdl.schemas[0].documentation["FindBook"],
+            "Looks a book up."
+        );
+        let Type::Complex(book) =
+            &wsdl.types[&QualifiedTypename::new("http://tempuri.org/library", "Book")]
+        else {
+            panic!("Book should be a complex type");
+        };
+        assert_eq!(
+            book.fields[0].1 .0.documentation.as_deref(),
+            Some("Identifier of the book.")
+        );
+        assert_eq!(book.fields[1].1 .0.documentation, None);
+        assert_eq!(
+            book.attributes[0].1 .0.documentation.as_deref(),
+            Some("Whether a copy can be borrowed.")
+        );
+
+        let port_type = &wsdl.port_types["LibrarySoap"];
+        assert_eq!(port_type.documentation.as_deref(), Some("Catalog queries."));
+        assert_eq!(
+            port_type.operations[0].documentation.as_deref(),
+            Some("Finds a book by its ISBN, if the library has it.")
+        );
+        assert_eq!(
+            wsdl.services[0].documentation.as_deref(),
+            Some("The city library.")
+        );
+    }
+
+    #[test]
+    fn parse_element_forms() {
+        let wsdl = parse(QUALIFIED_WSDL).unwrap();
+        assert_eq!(wsdl.schemas.len(), 2);
+        assert!(wsdl
+            .schemas
+            .iter()
+            .all(|s| s.element_form_default == Form::Qualified));
+        assert_eq!(
+            wsdl.messages["PlaceOrderSoapIn"]
+                .element()
+                .unwrap()
+                .namespace(),
+            "http://tempuri.org/orders"
+        );
+
+        let fields = |ns: &str, name: &str| match &wsdl.types
+            [&QualifiedTypename(ns.to_string(), name.to_string())]
+        {
+            Type::Complex(c) => c
+                .fields
+                .iter()
+                .map(|(name, (attributes, _))| (name.clone(), attributes.namespace.clone()))
+                .collect::<Vec<_>>(),
+            t => panic!("unexpected type {t:?}"),
+        };
+
+        let orders = Some("http://tempuri.org/orders".to_string());
+        let addresses = Some("http://tempuri.org/addresses".to_string());
+        assert_eq!(
+            fields("http://tempuri.org/orders", "PlaceOrder"),
+            vec![
+                ("Reference".to_string(), orders.clone()),
+                ("Quantity".to_string(), orders.clone()),
+                ("ShipTo".to_string(), orders),
+                ("Note".to_string(), addresses.clone()),
+            ]
+        );
+        let Type::Complex(address) = &wsdl.types[&QualifiedTypename(
+            "http://tempuri.org/addresses".to_string(),
+            "Address".to_string(),
+        )] else {
+            panic!("Address should be a complex type");
+        };
+        assert!(address.any && address.any_attribute);
+        assert_eq!(
+            fields("http://tempuri.org/addresses", "Address"),
+            vec![
+                ("Street".to_string(), addresses.clone()),
+                ("City".to_string(), addresses),
+                ("Country".to_string(), None),
+            ]
+        );
+    }
+}
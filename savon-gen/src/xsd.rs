@@ -0,0 +1,75 @@
+//! Standalone XML schema documents, for XML exchanged without a WSDL.
+
+use crate::wsdl::{parse_schemas, QualifiedTypename, Schema, Type, WsdlError};
+use std::collections::BTreeMap;
+use xmltree::Element;
+
+/// XML schema document.
+#[derive(Debug)]
+pub struct Xsd {
+    pub target_namespace: String,
+
+    pub schemas: Vec<Schema>,
+    pub types: BTreeMap<QualifiedTypename, Type>,
+}
+
+/// Parses an `.xsd` document, whose root is a `<schema>` element.
+pub fn parse(bytes: &[u8]) -> Result<Xsd, WsdlError> {
+    let root = Element::parse(bytes)?;
+    if root.name != "schema" {
+        return Err(WsdlError::ElementNotFound("schema"));
+    }
+
+    let target_namespace = root
+        .attributes
+        .get("targetNamespace")
+        .cloned()
+        .unwrap_or_default();
+    let (schemas, types) = parse_schemas([&root], &target_namespace)?;
+
+    debug!("parsed types: {:#?}", types);
+
+    Ok(Xsd {
+        target_namespace,
+        schemas,
+        types,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wsdl::SimpleType;
+
+    const ORDER_XSD: &[u8] = include_bytes!("../../assets/order.xsd");
+
+    #[test]
+    fn parse_order() {
+        let xsd = parse(ORDER_XSD).unwrap();
+
+        assert_eq!(xsd.target_namespace, "http://tempuri.org/order");
+        assert_eq!(
+            xsd.schemas[0]
+                .elements
+                .iter()
+                .map(|e| e.name.name())
+                .collect::<Vec<_>>(),
+            vec!["Order"]
+        );
+        let order = xsd.types.iter().find(|(name, _)| {
+            name.namespace() == "http://tempuri.org/order" && name.name() == "Order"
+        });
+        let Some((_, Type::Complex(order))) = order else {
+            panic!("Order should be a complex type");
+        };
+        assert!(matches!(
+            &order.fields[1].1 .1,
+            SimpleType::Complex(line) if line.name() == "Line"
+        ));
+
+        assert!(matches!(
+            parse(include_bytes!("../../assets/example.wsdl")),
+            Err(WsdlError::ElementNotFound("schema"))
+        ));
+    }
+}
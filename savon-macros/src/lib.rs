@@ -0,0 +1,92 @@
+//! Procedural macros generating the code of a WSDL document at compile time,
+//! instead of from a build script. They are re-exported by `savon`.
+
+use proc_macro::TokenStream;
+use std::path::PathBuf;
+use syn::{parse_macro_input, ItemMod, LitStr};
+
+/// Expands to the types, messages and clients of the WSDL document at the
+/// given path, relative to the directory of the crate manifest, like
+/// `savon::gen::gen`. With the `serde` feature of savon, the types derive
+/// serde traits:
+///
+/// ```ignore
+/// mod soap {
+///     savon::wsdl!("countryinfoservice.wsdl");
+/// }
+/// ```
+#[proc_macro]
+pub fn wsdl(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+
+    match expand(&path) {
+        Ok(generated) => generated.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Generates the code of the WSDL document at the given path into the
+/// module it is applied to, after its own items:
+///
+/// ```ignore
+/// #[savon::from_wsdl("countryinfoservice.wsdl")]
+/// mod soap {}
+/// ```
+#[proc_macro_attribute]
+pub fn from_wsdl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(attr as LitStr);
+    let module = parse_macro_input!(item as ItemMod);
+
+    let generated = match expand(&path) {
+        Ok(generated) => generated,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let ItemMod {
+        attrs,
+        vis,
+        unsafety,
+        ident,
+        content,
+        ..
+    } = module;
+    let items = content.map(|(_, items)| items).unwrap_or_default();
+
+    quote::quote! {
+        #(#attrs)*
+        #vis #unsafety mod #ident {
+            #(#items)*
+
+            #generated
+        }
+    }
+    .into()
+}
+
+/// Generates the code of the WSDL document at `path`. Errors are reported at
+/// the path literal.
+fn expand(path: &LitStr) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let error = |message: String| syn::Error::new(path.span(), message);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| error("CARGO_MANIFEST_DIR is not set".to_string()))?;
+    let full_path = PathBuf::from(manifest_dir).join(path.value());
+
+    let data = std::fs::read(&full_path)
+        .map_err(|e| error(format!("cannot read {}: {e}", full_path.display())))?;
+    let wsdl = savon_gen::wsdl::parse(&data[..])
+        .map_err(|e| error(format!("cannot parse {}: {e:?}", full_path.display())))?;
+    let generated = savon_gen::gen::Generator::new()
+        .serde(cfg!(feature = "serde"))
+        .generate(&wsdl)
+        .map_err(|e| error(format!("cannot generate {}: {e}", full_path.display())))?;
+
+    // including the document makes the compiler track it, so that changing
+    // it triggers a rebuild
+    let full_path = full_path.to_string_lossy();
+    Ok(quote::quote! {
+        #generated
+
+        const _: &[u8] = include_bytes!(#full_path);
+    })
+}
@@ -1,6 +1,19 @@
+use savon::gen::{Conversion, Generator};
 use std::env;
 
 fn main() {
     let out_dir = env::var("OUT_DIR").unwrap();
     savon::gen::gen_write("./countrinfoservice.wsdl", &out_dir).unwrap();
+
+    // the mapped types and their converter are defined by the `assets` tests
+    Generator::new()
+        .output("mappings.rs")
+        .map_type(
+            "Guid",
+            "super::Guid",
+            Conversion::Converter("super::Hyphenated".to_string()),
+        )
+        .map_type("xsd:long", "i64", Conversion::FromStr)
+        .generate_file("../assets/mappings.xsd", &out_dir)
+        .unwrap();
 }
@@ -0,0 +1,834 @@
+//! Code generated from the documents of `assets`, decoding and encoding
+//! sample messages.
+
+use savon::gen::{FromElement, ToElements};
+use savon::rpser::Response;
+
+mod defaults {
+    savon::wsdl!("../assets/defaults.wsdl");
+}
+
+mod documented {
+    savon::wsdl!("../assets/documented.wsdl");
+}
+
+mod faults {
+    savon::wsdl!("../assets/faults.wsdl");
+}
+
+mod identifiers {
+    savon::wsdl!("../assets/identifiers.wsdl");
+}
+
+mod lists {
+    savon::wsdl!("../assets/lists.wsdl");
+}
+
+// the generated `Guid` is unused, the fields of its type being mapped
+#[allow(dead_code)]
+mod mappings {
+    include!(concat!(env!("OUT_DIR"), "/mappings.rs"));
+}
+
+mod mixed {
+    savon::wsdl!("../assets/mixed.wsdl");
+}
+
+mod multiport {
+    savon::wsdl!("../assets/multiport.wsdl");
+}
+
+mod overloaded {
+    savon::wsdl!("../assets/overloaded.wsdl");
+}
+
+mod polymorphic {
+    savon::wsdl!("../assets/polymorphic.wsdl");
+}
+
+mod qualified {
+    savon::wsdl!("../assets/qualified.wsdl");
+}
+
+mod rpc_encoded {
+    savon::wsdl!("../assets/rpc-encoded.wsdl");
+}
+
+mod wsdl20 {
+    savon::wsdl!("../assets/wsdl20.wsdl");
+}
+
+/// A GUID, the `Guid` type of the mappings schema is mapped to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Guid(u128);
+
+/// Converter of GUIDs from and to their hyphenated form.
+struct Hyphenated;
+
+impl savon::gen::Convert<Guid> for Hyphenated {
+    fn from_text(text: &str) -> Result<Guid, savon::Error> {
+        let digits = text.trim().replace('-', "");
+        match u128::from_str_radix(&digits, 16) {
+            Ok(value) if digits.len() == 32 => Ok(Guid(value)),
+            _ => Err(savon::rpser::xml::Error::Conversion {
+                given: text.to_string(),
+                message: "not a GUID".to_string(),
+            }
+            .into()),
+        }
+    }
+
+    fn to_text(value: &Guid) -> String {
+        let digits = format!("{:032x}", value.0);
+        format!(
+            "{}-{}-{}-{}-{}",
+            &digits[..8],
+            &digits[8..12],
+            &digits[12..16],
+            &digits[16..20],
+            &digits[20..]
+        )
+    }
+}
+
+/// The body of a SOAP response envelope.
+fn body(xml: &str) -> savon::internal::xmltree::Element {
+    Response::from_xml(xml).unwrap().body
+}
+
+#[test]
+fn nil_array_items() {
+    let response = body(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+        <soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+            <soapenv:Body>
+                <ns1:getPermissionsResponse xmlns:ns1="urn:confluence">
+                    <getPermissionsReturn xsi:type="soapenc:Array" soapenc:arrayType="xsd:string[3]">
+                        <item>view</item>
+                        <item xsi:nil="true"/>
+                        <item>edit</item>
+                    </getPermissionsReturn>
+                </ns1:getPermissionsResponse>
+            </soapenv:Body>
+        </soapenv:Envelope>"#,
+    );
+
+    let response = rpc_encoded::GetPermissionsResponse::from_element(&response).unwrap();
+    let permissions = &response.get_permissions_return.0;
+    assert_eq!(
+        permissions,
+        &[Some("view".to_string()), None, Some("edit".to_string())]
+    );
+
+    let items = response.get_permissions_return.to_elements();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].get_text().unwrap(), "view");
+    assert_eq!(items[1].attributes["xsi:nil"], "true");
+}
+
+#[test]
+fn rpc_parts_round_trip() {
+    // the parts of the input are the accessors of the operation element
+    let request = rpc_encoded::GetPermissionsRequest {
+        in0: "token".to_string(),
+        in1: "DEV".to_string(),
+    };
+    let mut method = savon::rpser::Method::new("getPermissions");
+    for element in request.to_elements() {
+        method = method.with(element);
+    }
+    let envelope = method.as_xml("urn:confluence");
+    let envelope = savon::rpser::xml::parse(envelope.as_bytes()).unwrap();
+    let operation = envelope
+        .children
+        .iter()
+        .filter_map(|c| c.as_element())
+        .find(|e| e.name == "Body")
+        .and_then(|body| body.children.iter().find_map(|c| c.as_element()))
+        .unwrap();
+    assert_eq!(operation.name, "getPermissions");
+    assert_eq!(operation.namespace.as_deref(), Some("urn:confluence"));
+    let read = rpc_encoded::GetPermissionsRequest::from_element(operation).unwrap();
+    assert_eq!((read.in0.as_str(), read.in1.as_str()), ("token", "DEV"));
+
+    // the part of the output refers to multiRef values
+    let response = body(
+        r##"<?xml version="1.0" encoding="utf-8"?>
+        <soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+            <soapenv:Body>
+                <ns1:getSpacesResponse soapenv:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/" xmlns:ns1="urn:confluence">
+                    <getSpacesReturn soapenc:arrayType="ns2:RemoteSpace[2]" xsi:type="soapenc:Array" xmlns:ns2="http://beans.soap.rpc.confluence.atlassian.com">
+                        <getSpacesReturn href="#id0"/>
+                        <getSpacesReturn href="#id1"/>
+                    </getSpacesReturn>
+                </ns1:getSpacesResponse>
+                <multiRef id="id1" soapenc:root="0" xsi:type="ns3:RemoteSpace" xmlns:ns3="http://beans.soap.rpc.confluence.atlassian.com">
+                    <key xsi:type="xsd:string">DOC</key>
+                    <name xsi:type="xsd:string">Documentation</name>
+                </multiRef>
+                <multiRef id="id0" soapenc:root="0" xsi:type="ns4:RemoteSpace" xmlns:ns4="http://beans.soap.rpc.confluence.atlassian.com">
+                    <key xsi:type="xsd:string">DEV</key>
+                    <name xsi:nil="true"/>
+                </multiRef>
+            </soapenv:Body>
+        </soapenv:Envelope>"##,
+    );
+    let response = rpc_encoded::GetSpacesResponse::from_element(&response).unwrap();
+    let spaces = response
+        .get_spaces_return
+        .0
+        .iter()
+        .map(|space| {
+            let space = space.as_ref().unwrap();
+            (space.key.clone(), space.name.clone())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        spaces,
+        [
+            (Some("DEV".to_string()), None),
+            (Some("DOC".to_string()), Some("Documentation".to_string()))
+        ]
+    );
+}
+
+#[test]
+fn wildcard_attributes_round_trip() {
+    let xml = r#"<Address xmlns="http://tempuri.org/addresses" xmlns:ext="urn:ext"
+            xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+            ext:priority="high" ext:type="express" xsi:type="Address" id="7">
+        <Street>Main St</Street>
+        <City>Springfield</City>
+        <Country xmlns="">US</Country>
+    </Address>"#;
+    let element = savon::rpser::xml::parse(xml.as_bytes()).unwrap();
+    let mut address = qualified::Address::from_element(&element).unwrap();
+
+    // qualified attributes keep their namespace, and only xsi:type is dropped
+    let attributes = address
+        .any_attributes
+        .clone()
+        .into_iter()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        attributes,
+        [
+            ("id".to_string(), "7".to_string()),
+            ("{urn:ext}priority".to_string(), "high".to_string()),
+            ("{urn:ext}type".to_string(), "express".to_string()),
+        ]
+    );
+
+    address.city = "Shelbyville".to_string();
+    address
+        .any_attributes
+        .insert("{urn:ext}priority".to_string(), "low".to_string());
+    let written =
+        savon::gen::write_document(&address, Some("http://tempuri.org/addresses"), "Address");
+
+    let element = savon::rpser::xml::parse(written.as_bytes()).unwrap();
+    let read = qualified::Address::from_element(&element).unwrap();
+    assert_eq!(read.city, "Shelbyville");
+    assert_eq!(read.any_attributes["{urn:ext}priority"], "low");
+    assert_eq!(read.any_attributes["{urn:ext}type"], "express");
+    assert_eq!(read.any_attributes["id"], "7");
+}
+
+#[test]
+fn sanitized_identifiers_round_trip() {
+    let xml = r#"<Settings xmlns="http://tempuri.org/settings" id="s-1" xml-lang="fr">
+        <type>dark</type>
+        <match>true</match>
+        <self>me</self>
+        <ref><value>other</value></ref>
+        <kebab-name>kebab</kebab-name>
+        <dotted.name>3</dotted.name>
+        <ÄnderungsDatum>2024-01-01</ÄnderungsDatum>
+        <id>e-1</id>
+    </Settings>"#;
+    let element = savon::rpser::xml::parse(xml.as_bytes()).unwrap();
+    let settings = identifiers::Settings::from_element(&element).unwrap();
+
+    // fields are named after the sanitized XML names, keywords being escaped
+    assert_eq!(settings.r#type, "dark");
+    assert!(settings.r#match);
+    assert_eq!(settings.self_, "me");
+    assert_eq!(settings.r#ref.as_ref().unwrap().value, "other");
+    assert_eq!(settings.kebab_name, "kebab");
+    assert_eq!(settings.dotted_name, 3);
+    assert_eq!(settings.änderungs_datum, "2024-01-01");
+    // and an attribute sharing the name of an element gets a distinct one
+    assert_eq!(settings.id, "e-1");
+    assert_eq!(settings.id2.as_deref(), Some("s-1"));
+    assert_eq!(settings.xml_lang.as_deref(), Some("fr"));
+
+    // while the elements and attributes written keep their original names
+    let written =
+        savon::gen::write_document(&settings, Some("http://tempuri.org/settings"), "Settings");
+    let element = savon::rpser::xml::parse(written.as_bytes()).unwrap();
+    let names = element
+        .children
+        .iter()
+        .filter_map(|child| child.as_element())
+        .map(|child| child.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "type",
+            "match",
+            "self",
+            "ref",
+            "kebab-name",
+            "dotted.name",
+            "ÄnderungsDatum",
+            "id"
+        ]
+    );
+    assert_eq!(element.attributes["id"], "s-1");
+    assert_eq!(element.attributes["xml-lang"], "fr");
+    let read = identifiers::Settings::from_element(&element).unwrap();
+    assert_eq!(read.kebab_name, "kebab");
+    assert_eq!(read.änderungs_datum, "2024-01-01");
+    assert_eq!(read.id2.as_deref(), Some("s-1"));
+}
+
+#[test]
+fn xsi_type_dispatch() {
+    let xml = r#"<Checkout xmlns="http://tempuri.org/payments" xmlns:tns="http://tempuri.org/payments"
+            xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+        <Reference>R-1</Reference>
+        <Payments xsi:type="tns:CreditCardPayment" type="visa">
+            <Amount>10</Amount>
+            <CardNumber>4111</CardNumber>
+        </Payments>
+        <Payments xsi:type="tns:SepaTransferPayment">
+            <Amount>20</Amount>
+            <Iban>DE00</Iban>
+            <Bic>MARKDEF1</Bic>
+        </Payments>
+        <BankTransfer>
+            <Amount>30</Amount>
+            <Iban>FR00</Iban>
+        </BankTransfer>
+    </Checkout>"#;
+    let element = savon::rpser::xml::parse(xml.as_bytes()).unwrap();
+    let checkout = polymorphic::Checkout::from_element(&element).unwrap();
+
+    let check = |checkout: &polymorphic::Checkout| {
+        match &checkout.payments[..] {
+            [polymorphic::PaymentKind::CreditCardPayment(card), polymorphic::PaymentKind::SepaTransferPayment(sepa)] =>
+            {
+                // a declared attribute named `type` is not the xsi:type
+                assert_eq!(card.r#type.as_deref(), Some("visa"));
+                assert_eq!(card.card_number, "4111");
+                assert_eq!(sepa.bic.as_deref(), Some("MARKDEF1"));
+            }
+            other => panic!("unexpected payments {other:?}"),
+        }
+        match &checkout.payment_method {
+            Some(polymorphic::PaymentKind::BankTransferPayment(transfer)) => {
+                assert_eq!(transfer.iban, "FR00")
+            }
+            other => panic!("unexpected payment method {other:?}"),
+        }
+    };
+    check(&checkout);
+
+    // the derived types are written with their xsi:type, and read back
+    let written =
+        savon::gen::write_document(&checkout, Some("http://tempuri.org/payments"), "Checkout");
+    assert!(written.contains(":SepaTransferPayment\""));
+    let element = savon::rpser::xml::parse(written.as_bytes()).unwrap();
+    check(&polymorphic::Checkout::from_element(&element).unwrap());
+}
+
+#[test]
+fn mixed_content_order() {
+    let xml = r#"<tns:GetDocumentResponse xmlns:tns="http://tempuri.org/documents">
+        <Title>Notes</Title>
+        <Body>Hello <b>world</b>, see <link><Url>http://example.com</Url></link> now.</Body>
+    </tns:GetDocumentResponse>"#;
+    let element = savon::rpser::xml::parse(xml.as_bytes()).unwrap();
+    let response = mixed::GetDocumentResponse::from_element(&element).unwrap();
+
+    let check = |content: &[mixed::RichTextSegment]| match content {
+        [mixed::RichTextSegment::Text(hello), mixed::RichTextSegment::B(world), mixed::RichTextSegment::Text(see), mixed::RichTextSegment::Link(link), mixed::RichTextSegment::Text(now)] =>
+        {
+            assert_eq!(hello, "Hello ");
+            assert_eq!(world, "world");
+            assert_eq!(see, ", see ");
+            assert_eq!(link.url, "http://example.com");
+            assert_eq!(now, " now.");
+        }
+        other => panic!("unexpected content {other:?}"),
+    };
+    check(&response.body.content);
+
+    // text and elements are written back in the same order
+    let written = savon::gen::write_document(&response, None, "GetDocumentResponse");
+    assert!(written.contains(
+        "<Body>Hello <b>world</b>, see <link><Url>http://example.com</Url></link> now.</Body>"
+    ));
+    let element = savon::rpser::xml::parse(written.as_bytes()).unwrap();
+    check(
+        &mixed::GetDocumentResponse::from_element(&element)
+            .unwrap()
+            .body
+            .content,
+    );
+}
+
+#[test]
+fn defaults_and_fixed_values() {
+    let settings = |schema: &str, attributes: &str| {
+        let xml = format!(
+            r#"<Settings xmlns="http://tempuri.org/settings" id="3" {attributes}>
+                <PageSize>50</PageSize>
+                <Schema>{schema}</Schema>
+            </Settings>"#
+        );
+        let element = savon::rpser::xml::parse(xml.as_bytes()).unwrap();
+        defaults::Settings::from_element(&element)
+    };
+
+    // absent elements and attributes get their schema default
+    let read = settings("v2", r#"version="1.0""#).unwrap();
+    assert_eq!(read.language, "en");
+    assert_eq!(read.page_size, 50);
+    assert_eq!(read.zoom, -1.5);
+    assert!(read.notify);
+    assert_eq!(read.scope, "user");
+    assert_eq!(read.theme, None);
+
+    // fixed values are checked
+    assert!(matches!(
+        settings("v1", ""),
+        Err(savon::Error::Rpser(
+            savon::rpser::xml::Error::UnexpectedFixedValue { .. }
+        ))
+    ));
+    assert!(matches!(
+        settings("v2", r#"version="2.0""#),
+        Err(savon::Error::Rpser(
+            savon::rpser::xml::Error::UnexpectedFixedValue { .. }
+        ))
+    ));
+
+    // and written, along with the defaults
+    let default = defaults::Settings::default();
+    assert_eq!(default.page_size, 25);
+    let written =
+        savon::gen::write_document(&default, Some("http://tempuri.org/settings"), "Settings");
+    assert!(written.contains(r#"version="1.0""#));
+    assert!(written.contains("<ns:Schema>v2</ns:Schema>"));
+    let element = savon::rpser::xml::parse(written.as_bytes()).unwrap();
+    assert_eq!(
+        defaults::Settings::from_element(&element).unwrap().language,
+        "en"
+    );
+}
+
+#[test]
+fn lists_and_union_members() {
+    let report = |limit: &str, deadline: &str| {
+        let xml = format!(
+            r#"<GetReport xmlns="http://tempuri.org/reports">
+                <Years>2020  2021
+                    2022</Years>
+                <Tags>red green</Tags>
+                <Limit>{limit}</Limit>
+                <Deadline>{deadline}</Deadline>
+            </GetReport>"#
+        );
+        let element = savon::rpser::xml::parse(xml.as_bytes()).unwrap();
+        lists::GetReport::from_element(&element).unwrap()
+    };
+
+    let read = report("10", "2024-01-31T12:00:00Z");
+    assert_eq!(read.years.0, [2020, 2021, 2022]);
+    assert_eq!(read.tags.as_ref().unwrap().0, ["red", "green"]);
+    assert!(matches!(read.limit, lists::Limit::Int(10)));
+    assert!(matches!(read.deadline, Some(lists::Deadline::DateTime(_))));
+
+    // values invalid for a member fall back to the next one
+    let read = report("all", "1 2 3");
+    assert!(matches!(&read.limit, lists::Limit::Keyword(keyword) if keyword.0 == "all"));
+    match &read.deadline {
+        Some(lists::Deadline::DeadlineMember2(days)) => assert_eq!(days.0, [1, 2, 3]),
+        other => panic!("unexpected deadline {other:?}"),
+    }
+
+    let written =
+        savon::gen::write_document(&read, Some("http://tempuri.org/reports"), "GetReport");
+    assert!(written.contains("<ns:Years>2020 2021 2022</ns:Years>"));
+    assert!(written.contains("<ns:Deadline>1 2 3</ns:Deadline>"));
+}
+
+#[test]
+fn read_document_root() {
+    let note = r#"<Note xmlns="http://tempuri.org/addresses"><Text>hello</Text></Note>"#;
+    let read: qualified::Note = savon::gen::read_document(
+        note.as_bytes(),
+        Some("http://tempuri.org/addresses"),
+        "Note",
+    )
+    .unwrap();
+    assert_eq!(read.text, "hello");
+
+    // another element, or the same one in another namespace, is rejected
+    for (namespace, name) in [
+        (Some("http://tempuri.org/addresses"), "Address"),
+        (Some("http://tempuri.org/orders"), "Note"),
+        (None, "Note"),
+    ] {
+        let err = savon::gen::read_document::<qualified::Note>(note.as_bytes(), namespace, name)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            savon::Error::Rpser(savon::rpser::xml::Error::UnexpectedRootElement { ref given, .. })
+                if given == "{http://tempuri.org/addresses}Note"
+        ));
+    }
+}
+
+/// Serves a single HTTP request with the `status` and SOAP `envelope`, at the
+/// returned URL. The task resolves to the operation element of the request.
+async fn serve(
+    status: &'static str,
+    envelope: &'static str,
+) -> (
+    String,
+    tokio::task::JoinHandle<savon::internal::xmltree::Element>,
+) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let request = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = vec![];
+        let mut buf = [0; 4096];
+        // the whole request is read before answering, up to the end of its body
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request).to_lowercase();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map_or(0, |length| length.trim().parse().unwrap());
+                if request.len() >= end + 4 + length {
+                    break;
+                }
+            }
+        }
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            envelope.len(),
+            envelope
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+
+        let request = String::from_utf8(request).unwrap();
+        let envelope = &request[request.find("\r\n\r\n").unwrap() + 4..];
+        let envelope = savon::rpser::xml::parse(envelope.as_bytes()).unwrap();
+        envelope
+            .get_child("Body")
+            .and_then(|body| body.children.iter().find_map(|c| c.as_element()))
+            .unwrap()
+            .clone()
+    });
+
+    (url, request)
+}
+
+#[tokio::test]
+async fn declared_faults() {
+    const ACCESS_DENIED: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+        <soap:Body>
+            <soap:Fault>
+                <faultcode>soap:Client</faultcode>
+                <faultstring>Access denied</faultstring>
+                <detail>
+                    <AccessDenied xmlns="http://tempuri.org/search"><User>guest</User></AccessDenied>
+                </detail>
+            </soap:Fault>
+        </soap:Body>
+    </soap:Envelope>"#;
+    let search = || {
+        faults::SearchSoapIn(faults::Search {
+            query: "title:".to_string(),
+        })
+    };
+
+    // the fault is one of those of the admin operation
+    let client = faults::AdminSoap::new(serve("500 Internal Server Error", ACCESS_DENIED).await.0);
+    match client.search(search()).await {
+        Ok(Err(faults::AdminSoapSearchError::AccessDeniedFault(fault))) => {
+            assert_eq!(fault.0.user, "guest")
+        }
+        other => panic!("unexpected result {other:?}"),
+    }
+
+    // but not of the public one
+    let client = faults::PublicSoap::new(serve("500 Internal Server Error", ACCESS_DENIED).await.0);
+    match client.search(search()).await {
+        Err(savon::Error::Rpc(savon::rpser::RpcError::Fault { fault_string, .. })) => {
+            assert_eq!(fault_string, "Access denied")
+        }
+        other => panic!("unexpected result {other:?}"),
+    }
+
+    // one-way operations are answered with their faults too
+    let client = faults::AdminSoap::new(serve("500 Internal Server Error", ACCESS_DENIED).await.0);
+    let reindex = || {
+        faults::ReindexSoapIn(faults::Reindex {
+            index: "titles".to_string(),
+        })
+    };
+    match client.reindex(reindex()).await {
+        Ok(Err(faults::ReindexError::AccessDeniedFault(fault))) => {
+            assert_eq!(fault.0.user, "guest")
+        }
+        other => panic!("unexpected result {other:?}"),
+    }
+    let client = faults::AdminSoap::new(serve("202 Accepted", "").await.0);
+    assert!(matches!(client.reindex(reindex()).await, Ok(Ok(()))));
+}
+
+#[tokio::test]
+async fn client_per_port_type() {
+    const STOCK: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+        <soap:Body>
+            <GetStockResponse xmlns="http://tempuri.org/inventory"><Quantity>3</Quantity></GetStockResponse>
+        </soap:Body>
+    </soap:Envelope>"#;
+
+    // the public and admin port types of a service have their own clients
+    let (url, request) = serve("200 OK", STOCK).await;
+    let client = multiport::InventoryPublic::new(url);
+    let stock = client
+        .get_stock(multiport::GetStockSoapIn(multiport::GetStock {
+            sku: "A-1".to_string(),
+        }))
+        .await;
+    assert!(matches!(stock, Ok(Ok(multiport::GetStockSoapOut(ref s))) if s.quantity == 3));
+    let request = request.await.unwrap();
+    assert_eq!(request.name, "GetStock");
+    assert_eq!(
+        multiport::GetStock::from_element(&request).unwrap().sku,
+        "A-1"
+    );
+
+    let (url, request) = serve("202 Accepted", "").await;
+    let client = multiport::InventoryAdmin::new(url);
+    client
+        .set_stock(multiport::SetStockSoapIn(multiport::SetStock {
+            sku: "A-1".to_string(),
+            quantity: 5,
+        }))
+        .await
+        .unwrap();
+    let request = request.await.unwrap();
+    assert_eq!(request.name, "SetStock");
+    assert_eq!(
+        multiport::SetStock::from_element(&request)
+            .unwrap()
+            .quantity,
+        5
+    );
+
+    // and so does the port type of another service
+    let (url, request) = serve("202 Accepted", "").await;
+    let client = multiport::Monitoring::new(url);
+    client
+        .ping(multiport::PingSoapIn(multiport::Ping {}))
+        .await
+        .unwrap();
+    let request = request.await.unwrap();
+    assert_eq!(request.name, "Ping");
+    assert_eq!(
+        request.namespace.as_deref(),
+        Some("http://tempuri.org/inventory")
+    );
+}
+
+#[tokio::test]
+async fn overloaded_operations() {
+    const RESULT: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+        <soap:Body>
+            <SearchResult xmlns="http://tempuri.org/catalog"><Title>Dune</Title></SearchResult>
+        </soap:Body>
+    </soap:Envelope>"#;
+
+    // each overload of `Search` gets its own method, named after its input
+    let (url, request) = serve("200 OK", RESULT).await;
+    let client = overloaded::Catalog::new(url);
+    let result = client
+        .search_by_name(overloaded::SearchByNameIn(overloaded::SearchByName {
+            name: "Dune".to_string(),
+        }))
+        .await;
+    assert!(matches!(result, Ok(Ok(overloaded::SearchOut(ref r))) if r.title == ["Dune"]));
+    let request = request.await.unwrap();
+    assert_eq!(request.name, "SearchByName");
+    assert_eq!(
+        overloaded::SearchByName::from_element(&request)
+            .unwrap()
+            .name,
+        "Dune"
+    );
+
+    let (url, request) = serve("200 OK", RESULT).await;
+    let client = overloaded::Catalog::new(url);
+    let result = client
+        .search_by_id(overloaded::SearchByIdIn(overloaded::SearchById { id: 7 }))
+        .await;
+    assert!(matches!(result, Ok(Ok(_))));
+    let request = request.await.unwrap();
+    assert_eq!(
+        overloaded::SearchById::from_element(&request).unwrap().id,
+        7
+    );
+}
+
+#[tokio::test]
+async fn wsdl20_interfaces() {
+    const FORECAST: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+        <soap:Body>
+            <GetForecastResponse xmlns="http://tempuri.org/weather"><Temperature>21.5</Temperature></GetForecastResponse>
+        </soap:Body>
+    </soap:Envelope>"#;
+    // the binding is over SOAP 1.2, which the faults are answered with
+    const UNKNOWN_CITY: &str = r#"<env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope">
+        <env:Body>
+            <env:Fault>
+                <env:Code><env:Value>env:Sender</env:Value></env:Code>
+                <env:Reason><env:Text xml:lang="en">unknown city</env:Text></env:Reason>
+                <env:Detail>
+                    <UnknownCity xmlns="http://tempuri.org/weather"><City>Atlantis</City></UnknownCity>
+                </env:Detail>
+            </env:Fault>
+        </env:Body>
+    </env:Envelope>"#;
+
+    let (url, request) = serve("200 OK", FORECAST).await;
+    let client = wsdl20::Weather::new(url);
+    let forecast = client
+        .get_forecast(wsdl20::GetForecastInput(wsdl20::GetForecast {
+            city: "Paris".to_string(),
+        }))
+        .await;
+    assert!(matches!(forecast, Ok(Ok(wsdl20::GetForecastOutput(ref f))) if f.temperature == 21.5));
+    let request = request.await.unwrap();
+    assert_eq!(request.name, "GetForecast");
+    assert_eq!(
+        request.namespace.as_deref(),
+        Some("http://tempuri.org/weather")
+    );
+
+    // the interface faults its operations refer to are decoded
+    let (url, _) = serve("500 Internal Server Error", UNKNOWN_CITY).await;
+    let client = wsdl20::Weather::new(url);
+    let forecast = client
+        .get_forecast(wsdl20::GetForecastInput(wsdl20::GetForecast {
+            city: "Atlantis".to_string(),
+        }))
+        .await;
+    assert!(matches!(
+        forecast,
+        Ok(Err(wsdl20::GetForecastError::UnknownCityFault(wsdl20::UnknownCityFault(ref f))))
+            if f.city == "Atlantis"
+    ));
+
+    // and the operations of the interfaces it extends are inherited
+    let (url, request) = serve("202 Accepted", "").await;
+    let client = wsdl20::Weather::new(url);
+    client
+        .submit_report(wsdl20::SubmitReportInput(wsdl20::Report {
+            station: "Orly".to_string(),
+            temperature: 19.0,
+        }))
+        .await
+        .unwrap();
+    let request = request.await.unwrap();
+    assert_eq!(
+        wsdl20::Report::from_element(&request).unwrap().station,
+        "Orly"
+    );
+}
+
+#[tokio::test]
+async fn documented_operations() {
+    const BOOK: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+        <soap:Body>
+            <FindBookResponse xmlns="http://tempuri.org/library">
+                <Book available="true"><Isbn>9780441013593</Isbn><Title>Dune</Title></Book>
+            </FindBookResponse>
+        </soap:Body>
+    </soap:Envelope>"#;
+
+    // the annotations of the schema and port type are only documentation
+    let (url, request) = serve("200 OK", BOOK).await;
+    let client = documented::Library::new(url);
+    let found = client
+        .find_book(documented::FindBookSoapIn(documented::FindBook {
+            isbn: documented::Isbn("9780441013593".to_string()),
+        }))
+        .await;
+    let book = match found {
+        Ok(Ok(documented::FindBookSoapOut(found))) => found.book.unwrap(),
+        other => panic!("unexpected result: {:?}", other),
+    };
+    assert_eq!(book.isbn.0, "9780441013593");
+    assert_eq!(book.title, "Dune");
+    assert_eq!(book.available, Some(true));
+    let request = request.await.unwrap();
+    assert_eq!(
+        documented::FindBook::from_element(&request).unwrap().isbn.0,
+        "9780441013593"
+    );
+}
+
+#[test]
+fn mapped_types_convert() {
+    let xml = r#"<Shipment xmlns="http://tempuri.org/shipment" tracking="0000000A-0000-0000-0000-00000000000B">
+        <Id>6F9619FF-8B86-D011-B42D-00C04FC964FF</Id>
+        <Weight>12000000000</Weight>
+        <Parcels>00000000-0000-0000-0000-000000000001</Parcels>
+        <Parcels>00000000-0000-0000-0000-000000000002</Parcels>
+        <Destination><Street>Main St</Street><City>Springfield</City></Destination>
+    </Shipment>"#;
+    let shipment = mappings::read_shipment(xml.as_bytes()).unwrap();
+
+    // elements, repeated elements and attributes of a mapped type go through
+    // its converter
+    assert_eq!(shipment.id, Guid(0x6f9619ff_8b86_d011_b42d_00c04fc964ff));
+    assert_eq!(shipment.weight, 12_000_000_000);
+    assert_eq!(shipment.parcels, [Guid(1), Guid(2)]);
+    assert_eq!(
+        shipment.tracking,
+        Some(Guid(0xa_0000_0000_0000_0000_0000_000b))
+    );
+
+    let written = mappings::write_shipment(&shipment);
+    let element = savon::rpser::xml::parse(written.as_bytes()).unwrap();
+    let id = element.get_child("Id").unwrap();
+    assert_eq!(
+        id.get_text().as_deref(),
+        Some("6f9619ff-8b86-d011-b42d-00c04fc964ff")
+    );
+    assert_eq!(
+        element.attributes["tracking"],
+        "0000000a-0000-0000-0000-00000000000b"
+    );
+    let read = mappings::Shipment::from_element(&element).unwrap();
+    assert_eq!(read.parcels, shipment.parcels);
+
+    // and the values it does not convert are errors
+    let invalid = xml.replace("6F9619FF-8B86", "not-a-guid");
+    assert!(mappings::read_shipment(invalid.as_bytes()).is_err());
+}
@@ -1,10 +1,23 @@
 #[macro_use]
 extern crate log;
 
+#[cfg(test)]
+mod assets;
+
 mod soap {
     include!(concat!(env!("OUT_DIR"), "/example.rs"));
 }
 
+// the same code, generated by the macros instead of the build script
+#[allow(dead_code)]
+mod inline_soap {
+    savon::wsdl!("countrinfoservice.wsdl");
+}
+
+#[allow(dead_code)]
+#[savon::from_wsdl("countrinfoservice.wsdl")]
+mod attribute_soap {}
+
 #[tokio::main]
 async fn main() -> Result<(), savon::Error> {
     pretty_env_logger::init();
@@ -0,0 +1,106 @@
+//! Vectors carrying the occurrence bounds of a schema element.
+
+use std::fmt;
+use std::ops::Deref;
+
+/// A vector holding between `MIN` and `MAX` items.
+///
+/// Generated for repeated fields with `minOccurs` of 1 or more or a finite
+/// `maxOccurs` when `GenOptions::bounded_vecs` is set. The bounds are checked
+/// when converting from a `Vec`, when pushing and when decoding; the `Default`
+/// value is empty so that generated types can still derive `Default`, even if
+/// `MIN` is above 0.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundedVec<T, const MIN: usize, const MAX: usize>(Vec<T>);
+
+/// Error returned when a `BoundedVec` would hold a number of items outside its bounds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundsError {
+    pub len: usize,
+    pub min: usize,
+    pub max: usize,
+}
+
+impl fmt::Display for BoundsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} items found, expected between {} and {}",
+            self.len, self.min, self.max
+        )
+    }
+}
+
+impl std::error::Error for BoundsError {}
+
+impl<T, const MIN: usize, const MAX: usize> BoundedVec<T, MIN, MAX> {
+    /// Appends an item, giving it back if the vector is already full.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.0.len() >= MAX {
+            return Err(item);
+        }
+
+        self.0.push(item);
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> Default for BoundedVec<T, MIN, MAX> {
+    fn default() -> Self {
+        BoundedVec(Vec::new())
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> TryFrom<Vec<T>> for BoundedVec<T, MIN, MAX> {
+    type Error = BoundsError;
+
+    fn try_from(v: Vec<T>) -> Result<Self, Self::Error> {
+        if v.len() < MIN || v.len() > MAX {
+            return Err(BoundsError {
+                len: v.len(),
+                min: MIN,
+                max: MAX,
+            });
+        }
+
+        Ok(BoundedVec(v))
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> Deref for BoundedVec<T, MIN, MAX> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> From<BoundedVec<T, MIN, MAX>> for Vec<T> {
+    fn from(v: BoundedVec<T, MIN, MAX>) -> Self {
+        v.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, const MIN: usize, const MAX: usize> serde::Serialize
+    for BoundedVec<T, MIN, MAX>
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+/// The bounds are checked when deserializing too.
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>, const MIN: usize, const MAX: usize> serde::Deserialize<'de>
+    for BoundedVec<T, MIN, MAX>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let v = Vec::<T>::deserialize(deserializer)?;
+        BoundedVec::try_from(v).map_err(serde::de::Error::custom)
+    }
+}
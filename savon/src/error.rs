@@ -3,7 +3,12 @@ pub enum Error {
     Wsdl(crate::wsdl::WsdlError),
     Reqwest(reqwest::Error),
     Rpser(crate::rpser::xml::Error),
+    /// Fault not declared by the operation, or invalid response envelope.
+    Rpc(crate::rpser::RpcError),
     Num(std::num::ParseFloatError),
+    Bounds(crate::bounded::BoundsError),
+    Xml(xmltree::ParseError),
+    MissingField(crate::gen::MissingField),
 }
 
 impl From<crate::wsdl::WsdlError> for Error {
@@ -18,6 +23,12 @@ impl From<crate::rpser::xml::Error> for Error {
     }
 }
 
+impl From<crate::rpser::RpcError> for Error {
+    fn from(e: crate::rpser::RpcError) -> Self {
+        Error::Rpc(e)
+    }
+}
+
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Self {
         Error::Reqwest(e)
@@ -29,3 +40,21 @@ impl From<std::num::ParseFloatError> for Error {
         Error::Num(e)
     }
 }
+
+impl From<crate::bounded::BoundsError> for Error {
+    fn from(e: crate::bounded::BoundsError) -> Self {
+        Error::Bounds(e)
+    }
+}
+
+impl From<xmltree::ParseError> for Error {
+    fn from(e: xmltree::ParseError) -> Self {
+        Error::Xml(e)
+    }
+}
+
+impl From<crate::gen::MissingField> for Error {
+    fn from(e: crate::gen::MissingField) -> Self {
+        Error::MissingField(e)
+    }
+}
@@ -1,11 +1,27 @@
-use crate::string;
-use crate::wsdl::{parse, QualifiedTypename, SimpleType, Type, Wsdl};
-use case::CaseExt;
-use proc_macro2::{Ident, Literal, Span, TokenStream};
-use std::{fs::File, io::Write};
+//! Runtime support of the generated code. The code generation itself lives in
+//! the `savon-gen` crate, re-exported here.
+
+use crate::rpser::xml::BuildElement;
+
+pub use savon_gen::gen::*;
 
 pub trait ToElements {
     fn to_elements(&self) -> Vec<xmltree::Element>;
+
+    /// Attributes of the element the value is written to, only types with an
+    /// `<anyAttribute/>` wildcard have any.
+    fn to_attributes(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    /// Text and elements of the element the value is written to, only types
+    /// with mixed content have text.
+    fn to_nodes(&self) -> Vec<xmltree::XMLNode> {
+        self.to_elements()
+            .into_iter()
+            .map(xmltree::XMLNode::Element)
+            .collect()
+    }
 }
 
 pub trait FromElement {
@@ -14,6 +30,23 @@ pub trait FromElement {
         Self: Sized;
 }
 
+/// Faults of an operation, the errors returned by its client method. They are
+/// decoded from the `detail` of a SOAP Fault.
+pub trait FromFault {
+    /// Decodes `detail`, or returns `None` when it does not hold one of the
+    /// faults of the operation.
+    fn from_fault(detail: &xmltree::Element) -> Option<Result<Self, crate::Error>>
+    where
+        Self: Sized;
+}
+
+/// Operations without faults.
+impl FromFault for () {
+    fn from_fault(_detail: &xmltree::Element) -> Option<Result<Self, crate::Error>> {
+        None
+    }
+}
+
 impl<T: ToElements> ToElements for Option<T> {
     fn to_elements(&self) -> Vec<xmltree::Element> {
         match self {
@@ -21,496 +54,131 @@ impl<T: ToElements> ToElements for Option<T> {
             None => vec![],
         }
     }
-}
-
-/*impl<T: ToElements> for Vec<T> {
-    fn to_elements(&self) -> Vec<xmltree::Element> {
 
+    fn to_attributes(&self) -> Vec<(String, String)> {
         match self {
-            Some(e) => e.to_elements(),
+            Some(e) => e.to_attributes(),
             None => vec![],
         }
     }
-}*/
-
-#[derive(Debug)]
-pub enum GenError {
-    Io(std::io::Error),
-}
-
-impl From<std::io::Error> for GenError {
-    fn from(e: std::io::Error) -> Self {
-        GenError::Io(e)
-    }
-}
-
-fn gen_simple(ty: &SimpleType) -> Ident {
-    match ty {
-        SimpleType::Boolean => Ident::new("bool", Span::call_site()),
-        SimpleType::String => Ident::new("String", Span::call_site()),
-        SimpleType::Float => Ident::new("f64", Span::call_site()),
-        SimpleType::Int => Ident::new("i64", Span::call_site()),
-        SimpleType::DateTime => Ident::new("chrono::DateTime", Span::call_site()),
-        SimpleType::Base64Binary => Ident::new("String", Span::call_site()), // TODO: Base64 type...
-        SimpleType::Complex(n) => Ident::new(&n.name().to_camel(), Span::call_site()),
-    }
-}
-
-fn gen_type(name: &QualifiedTypename, t: &Type) -> TokenStream {
-    let type_name = Ident::new(&name.name().to_camel(), Span::call_site());
-
-    match t {
-        Type::Complex(c) => {
-            let fields = c
-                .fields
-                .iter()
-                .map(|(field_name, (attributes, field_type))| {
-                    let tgt = if let SimpleType::Complex(n) = field_type {
-                        Some(n.clone())
-                    } else {
-                        None
-                    };
-
-                    let fname = Ident::new(&string::to_snake(field_name), Span::call_site());
-                    let ft = gen_simple(field_type);
-
-                    let ft = match (
-                        attributes.min_occurs.as_ref(),
-                        attributes.max_occurs.as_ref(),
-                    ) {
-                        (Some(_), Some(_)) => quote! { Vec<#ft> },
-                        _ => quote! { #ft },
-                    };
-                    let ft = if attributes.nillable {
-                        quote! { Option<#ft> }
-                    } else {
-                        ft
-                    };
-
-                    let docstr = if let Some(tgt) = tgt {
-                        format!(" Qualified type: {tgt}")
-                    } else {
-                        String::new()
-                    };
-
-                    quote! {
-                        #[doc = #docstr]
-                        pub #fname: #ft,
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            let fields_serialize_impl = c
-                .fields
-                .iter()
-                .map(|(field_name, (attributes, field_type))| {
-                    let fname = Ident::new(&string::to_snake(field_name), Span::call_site());
-                    //FIXME: handle more complex types
-                    /*let ft = match field_type {
-                        SimpleType::Boolean => Ident::new("bool", Span::call_site()),
-                        SimpleType::String => Ident::new("String", Span::call_site()),
-                        SimpleType::Float => Ident::new("f64", Span::call_site()),
-                        SimpleType::Int => Ident::new("i64", Span::call_site()),
-                        SimpleType::DateTime => Ident::new("String", Span::call_site()),
-                        SimpleType::Complex(s) => Ident::new(&s, Span::call_site()),
-                    };*/
-                    let ftype = Literal::string(field_name);
-                    let prefix = quote! { xmltree::Element::node(#ftype) };
-
-                    match (
-                        attributes.min_occurs.as_ref(),
-                        attributes.max_occurs.as_ref(),
-                    ) {
-                        (Some(_), Some(_)) => {
-                            if attributes.nillable {
-                                quote! {
-                                    self.#fname.as_ref().map(|v| v.iter().map(|i| {
-                                        #prefix.with_children(i.to_elements())
-                                    }).collect::<Vec<_>>()).unwrap_or_else(Vec::new)
-                                }
-                            } else {
-                                quote! {
-                                    self.#fname.iter().map(|i| {
-                                        #prefix.with_children(i.to_elements())
-                                    }).collect::<Vec<_>>()
-                                }
-                            }
-                        }
-                        _ => match field_type {
-                            SimpleType::Complex(_s) => {
-                                quote! { vec![#prefix.with_children(self.#fname.to_elements())]}
-                            }
-                            _ => quote! { vec![#prefix.with_text(self.#fname.to_string())] },
-                        },
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            let serialize_impl = if fields_serialize_impl.is_empty() {
-                quote! {
-                    impl savon::gen::ToElements for #type_name {
-                        fn to_elements(&self) -> Vec<xmltree::Element> {
-                            vec![]
-                        }
-                    }
-                }
-            } else {
-                quote! {
-                    impl savon::gen::ToElements for #type_name {
-                        fn to_elements(&self) -> Vec<xmltree::Element> {
-                            vec![#(#fields_serialize_impl),*].drain(..).flatten().collect()
-                        }
-                    }
-                }
-            };
-
-            let fields_deserialize_impl = c
-            .fields
-            .iter()
-            .map(|(field_name, (attributes, field_type))| {
-                let fname = Ident::new(&string::to_snake(field_name), Span::call_site());
-                let ftype = Literal::string(field_name);
-
-                let prefix = quote!{ #fname: element.get_at_path(&[#ftype]) };
-
-                match field_type {
-                    SimpleType::Base64Binary => {
-                        // TODO: Properly parse this...
-                        let ft = quote!{ #prefix.and_then(|e| e.get_text().map(|s| s.to_string())
-                                              .ok_or(savon::rpser::xml::Error::Empty)
-                                              ) };
-                        if attributes.nillable {
-                            quote!{ #ft.ok(),}
-                        } else {
-                            quote!{ #ft?,}
-                        }
-                    }
-                    SimpleType::Boolean => {
-                        let ft = quote!{ #prefix.and_then(|e| e.as_boolean()) };
-                        if attributes.nillable {
-                            quote!{ #ft.ok(),}
-                        } else {
-                            quote!{ #ft?,}
-                        }
-                    },
-                    SimpleType::String => {
-                        let ft = quote!{ #prefix.and_then(|e| e.get_text().map(|s| s.to_string())
-                                             .ok_or(savon::rpser::xml::Error::Empty)
-                                             ) };
-                        if attributes.nillable {
-                            quote!{ #ft.ok(),}
-                        } else {
-                            quote!{ #ft?,}
-                        }
-                    },
-                    SimpleType::Float => {
-                        let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| e.get_text()
-                                             .ok_or(savon::rpser::xml::Error::Empty)
-                                             .map_err(savon::Error::from)
-                                             .and_then(|s| s.parse().map_err(savon::Error::from))) };
-                        if attributes.nillable {
-                            quote!{ #ft.ok(),}
-                        } else {
-                            quote!{ #ft?,}
-                        }
-                    },
-                    SimpleType::Int => {
-                        let ft = quote!{ #prefix.and_then(|e| e.as_long()) };
-                        if attributes.nillable {
-                            quote!{ #ft.ok(),}
-                        } else {
-                            quote!{ #ft?,}
-                        }
-                    },
-                    SimpleType::DateTime => {
-                        let ft = quote!{
-                            #prefix.and_then(|e| e.get_text()
-                                             .ok_or(savon::rpser::xml::Error::Empty)
-                                             ).map_err(savon::Error::from)
-                            .and_then(|s|
-                                      s.parse::<savon::internal::chrono::DateTime<savon::internal::chrono::offset::Utc>>().map_err(savon::Error::from))
-                        };
-                        if attributes.nillable {
-                            quote!{ #ft.ok(),}
-                        } else {
-                            quote!{ #ft?,}
-                        }
-                    },
-                    SimpleType::Complex(n) => {
-                        let complex_type = Ident::new(&n.name().to_camel(), Span::call_site());
-
-                        match (attributes.min_occurs.as_ref(), attributes.max_occurs.as_ref()) {
-                            (Some(_), Some(_)) => {
-                                let ft = quote! {
-                                    {
-                                        let mut v = vec![];
-                                        for elem in element.children.iter()
-                                            .filter_map(|c| c.as_element()) {
-                                                v.push(#complex_type::from_element(elem)?);
-                                            }
-                                        v
-                                    },
-                                };
-
-                                if attributes.nillable {
-                                    quote!{ #fname: Some(#ft) }
-                                } else {
-                                    quote!{ #fname: #ft }
-                                }
-                            },
-                            _ => {
-                                let ft = quote!{ #prefix.map_err(savon::Error::from).and_then(|e| #complex_type::from_element(&e).map_err(savon::Error::from)) };
-                                if attributes.nillable {
-                                    quote!{ #ft.ok(),}
-                                } else {
-                                    quote!{ #ft?,}
-                                }
-                            }
-                        }
-                    },
-                }
-            })
-            .collect::<Vec<_>>();
-
-            let deserialize_impl = if fields_deserialize_impl.is_empty() {
-                quote! {
-                    impl savon::gen::FromElement for #type_name {
-                        fn from_element(_element: &xmltree::Element) -> Result<Self, savon::Error> {
-                            Ok(#type_name {
-                            })
-                        }
-                    }
-                }
-            } else {
-                quote! {
-                    impl savon::gen::FromElement for #type_name {
-                        fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
-                            Ok(#type_name {
-                                #(#fields_deserialize_impl)*
-                            })
-                        }
-                    }
-                }
-            };
-
-            let docstr = format!(" Qualified type: {}", name);
-
-            quote! {
-                #[doc = #docstr]
-                #[derive(Clone, Debug, Default)]
-                pub struct #type_name {
-                    #(#fields)*
-                }
-
-                #serialize_impl
-
-                #deserialize_impl
-            }
-        }
-        Type::Simple(t) => {
-            let ident = gen_simple(t);
-            let field = quote! { pub #ident };
-
-            // TODO: Serialize/deserialize impls
-
-            let docstr = format!(" Qualified type: {}", name);
 
-            quote! {
-                #[doc = #docstr]
-                #[derive(Clone, Debug, Default)]
-                pub struct #type_name( #field );
-            }
+    fn to_nodes(&self) -> Vec<xmltree::XMLNode> {
+        match self {
+            Some(e) => e.to_nodes(),
+            None => vec![],
         }
-        _ => panic!(),
     }
 }
 
-pub fn gen_write(path: &str, out: &str) -> Result<(), crate::Error> {
-    let out_path = format!("{}/example.rs", out);
-    let v = std::fs::read(path).unwrap();
-    let mut output = File::create(out_path).unwrap();
-    let wsdl = parse(&v[..]).unwrap();
-
-    let generated = gen(&wsdl).unwrap();
-    let formatted = prettyplease::unparse(&syn::parse_quote!(#generated));
+/// Conversion of the values of a type mapped by the generator, see
+/// `GenOptions::type_mappings`, from and to the text of elements and
+/// attributes. It is implemented by a converter rather than by the type
+/// itself, so that types of other crates can be converted too.
+pub trait Convert<T> {
+    fn from_text(text: &str) -> Result<T, crate::Error>;
 
-    output.write_all(formatted.as_bytes()).unwrap();
-    output.flush().unwrap();
-
-    Ok(())
+    fn to_text(value: &T) -> String;
 }
 
-pub fn gen(wsdl: &Wsdl) -> Result<TokenStream, GenError> {
-    let target_namespace = Literal::string(&wsdl.target_namespace);
-
-    let operations = wsdl.operations.iter().map(|(name, operation)| {
-        let op_name = Ident::new(&string::to_snake(name), Span::call_site());
-        let input_name = Ident::new(&string::to_snake(operation.input.as_ref().unwrap()), Span::call_site());
-        let input_type = Ident::new(&operation.input.as_ref().unwrap().to_camel(), Span::call_site());
-
-        let op_str = Literal::string(name);
-
-        match (operation.output.as_ref(), operation.faults.as_ref()) {
-            (None, None) => {
-                quote! {
-                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<(), savon::Error> {
-                        savon::http::one_way(&self.client, &self.base_url, #target_namespace, #op_str, &#input_name).await
-                    }
-                }
-            },
-            (None, Some(_)) => quote!{},
-            (Some(out), None) => {
-                let out_name = Ident::new(out, Span::call_site());
-
-                quote! {
-                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<Result<#out_name, ()>, savon::Error> {
-                        savon::http::request_response(&self.client, &self.base_url, #target_namespace, #op_str, &#input_name).await
-                    }
-                }
-            },
-            (Some(out), Some(_)) => {
-                let out_name = Ident::new(out, Span::call_site());
-                let err_name = Ident::new(&format!("{}Error", name.to_camel()), Span::call_site());
-                let input_name = Ident::new(&string::to_snake(&format!("_{}", operation.input.as_ref().unwrap())), Span::call_site());
-
-                quote! {
-                    pub async fn #op_name(&self, #input_name: #input_type) -> Result<Result<#out_name, #err_name>, savon::Error> {
-                        unimplemented!()
-                        /*let req = hyper::http::request::Builder::new()
-                            .method("POST")
-                            .header("Content-Type", "text/xml-SOAP")
-                            .header("MessageType", "Call")
-                            .body(#input_name.as_xml())?;
-
-                        let response: hyper::http::Response<String> = self.client.request(req).await?;
-                        let body = response.body().await?;
-                        if let Ok(out) = #out_name::from_xml(body) {
-                            Ok(Ok(out))
-                        } else {
-                            Ok(#err_name::from_xml(body)?)
-                        }
-                        */
-                    }
-                }
-            },
-        }
-    }).collect::<Vec<_>>();
-
-    let types = wsdl
-        .types
-        .iter()
-        .map(|(name, t)| gen_type(name, t))
-        .collect::<Vec<_>>();
-
-    let messages = wsdl
-        .messages
-        .iter()
-        .map(|(message_name, message)| {
-            let mname = Ident::new(message_name, Span::call_site());
-            let iname = Ident::new(&message.part_element, Span::call_site());
-
-            quote! {
-                #[derive(Clone, Debug, Default)]
-                pub struct #mname(pub #iname);
-
-                impl savon::gen::ToElements for #mname {
-                    fn to_elements(&self) -> Vec<xmltree::Element> {
-                        self.0.to_elements()
-                    }
-                }
-
-                impl savon::gen::FromElement for #mname {
-                    fn from_element(element: &xmltree::Element) -> Result<Self, savon::Error> {
-                        #iname::from_element(element).map(#mname)
-                    }
-                }
+/// Converter of the types implementing `FromStr` and `Display`, e.g.
+/// `uuid::Uuid`.
+pub struct DisplayFromStr;
+
+impl<T> Convert<T> for DisplayFromStr
+where
+    T: std::str::FromStr + std::fmt::Display,
+    T::Err: std::fmt::Display,
+{
+    fn from_text(text: &str) -> Result<T, crate::Error> {
+        text.parse().map_err(|e: T::Err| {
+            crate::rpser::xml::Error::Conversion {
+                given: text.to_string(),
+                message: e.to_string(),
             }
+            .into()
         })
-        .collect::<Vec<_>>();
-
-    let service_name = Ident::new(&wsdl.name, Span::call_site());
-
-    let toks = quote! {
-        use savon::internal::xmltree;
-        #[allow(unused_imports)]
-        use savon::rpser::xml::*;
-
-        #(#types)*
-
-        pub struct #service_name {
-            pub base_url: String,
-            pub client: savon::internal::reqwest::Client,
-        }
+    }
 
-        #(#messages)*
+    fn to_text(value: &T) -> String {
+        value.to_string()
+    }
+}
 
-        #[allow(dead_code)]
-        impl #service_name {
-            pub fn new(base_url: String) -> Self {
-                Self::with_client(base_url, savon::internal::reqwest::Client::new())
-            }
+/// Error returned by the builder of a generated type when one of its required
+/// fields was not set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingField {
+    /// Schema type of the built value.
+    pub type_name: &'static str,
+    /// XML name of the field.
+    pub field: &'static str,
+}
 
-            pub fn with_client(base_url: String, client: savon::internal::reqwest::Client) -> Self {
-                #service_name {
-                    base_url,
-                    client,
-                }
-            }
+impl std::fmt::Display for MissingField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "required field {} of {} is not set",
+            self.field, self.type_name
+        )
+    }
+}
 
-            #(#operations)*
+impl std::error::Error for MissingField {}
+
+/// Reads a whole XML document, whose root element `name` in `namespace` is a
+/// `T`.
+pub fn read_document<T: FromElement>(
+    xml: &[u8],
+    namespace: Option<&str>,
+    name: &str,
+) -> Result<T, crate::Error> {
+    let root = crate::rpser::xml::parse(xml)?;
+    if root.name != name || root.namespace.as_deref() != namespace {
+        let qualified = |namespace: Option<&str>, name: &str| match namespace {
+            Some(ns) => format!("{{{ns}}}{name}"),
+            None => name.to_string(),
+        };
+        return Err(crate::rpser::xml::Error::UnexpectedRootElement {
+            expected: qualified(namespace, name),
+            given: qualified(root.namespace.as_deref(), &root.name),
         }
-    };
-
-    let operation_faults = wsdl
-        .operations
-        .iter()
-        .filter(|(_, op)| op.faults.is_some())
-        .map(|(name, operation)| {
-            let op_error = Ident::new(&format!("{}Error", name), Span::call_site());
-
-            let faults = operation
-                .faults
-                .as_ref()
-                .unwrap()
-                .iter()
-                .map(|fault| {
-                    let fault_name = Ident::new(fault, Span::call_site());
-
-                    quote! {
-                          #fault_name(#fault_name),
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            quote! {
-                #[derive(Clone, Debug)]
-                pub enum #op_error {
-                    #(#faults)*
-                }
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let mut stream: TokenStream = toks;
-    stream.extend(operation_faults);
-
-    Ok(stream)
+        .into());
+    }
+    T::from_element(&root)
 }
 
-#[cfg(test)]
-#[allow(dead_code)]
-mod tests {
-    use super::*;
-    const WIKIPEDIA_WSDL: &[u8] = include_bytes!("../../assets/wikipedia-example.wsdl");
-    const EXAMPLE_WSDL: &[u8] = include_bytes!("../../assets/example.wsdl");
+/// Writes `value` as a whole XML document, with the root element `name` in
+/// `namespace`. Namespaces are bound to the `ns`, `ns1`, etc. prefixes.
+pub fn write_document<T: ToElements>(value: &T, namespace: Option<&str>, name: &str) -> String {
+    let mut root = xmltree::Element::node(name)
+        .with_attrs(value.to_attributes())
+        .with_nodes(value.to_nodes());
+    root.namespace = namespace.map(|ns| ns.to_string());
+
+    let mut namespaces = vec![];
+    crate::rpser::qualify(&mut root, &mut namespaces);
+    for (i, ns) in namespaces.iter().enumerate() {
+        let prefix = match i {
+            0 => "xmlns:ns".to_string(),
+            i => format!("xmlns:ns{}", i),
+        };
+        root.attributes.insert(prefix, ns.clone());
+    }
+    root.attributes.insert(
+        "xmlns:xsi".to_string(),
+        "http://www.w3.org/2001/XMLSchema-instance".to_string(),
+    );
 
-    #[test]
-    fn example() {
-        let wsdl = parse(EXAMPLE_WSDL).unwrap();
-        println!("wsdl: {:?}", wsdl);
+    root.to_string()
+}
 
-        let res = gen(&wsdl).unwrap();
+/*impl<T: ToElements> for Vec<T> {
+    fn to_elements(&self) -> Vec<xmltree::Element> {
 
-        println!("generated:\n{}", res);
+        match self {
+            Some(e) => e.to_elements(),
+            None => vec![],
+        }
     }
-}
+}*/
@@ -1,15 +1,10 @@
-use crate::gen::{FromElement, ToElements};
-use crate::rpser::{Method, Response};
+use crate::gen::{FromElement, FromFault, ToElements};
+use crate::rpser::{Method, Response, RpcError};
 use reqwest::Client;
 use std::fmt::Debug;
 
-pub async fn one_way<Input: ToElements>(
-    client: &Client,
-    base_url: &str,
-    ns: &str,
-    method: &str,
-    input: &Input,
-) -> Result<(), crate::Error> {
+/// Builds the envelope of a call to `method` with `input` as its arguments.
+fn envelope<Input: ToElements>(ns: &str, method: &str, input: &Input) -> String {
     let mut v = input.to_elements();
     let mut m = Method::new(method);
 
@@ -18,12 +13,72 @@ pub async fn one_way<Input: ToElements>(
     }
     let s = m.as_xml(ns);
     trace!("sending: {}", s);
+    s
+}
+
+/// Decodes the output of a call from the response envelope, or the fault of
+/// the operation it contains.
+fn decode<Output: Debug + FromElement, Error: Debug + FromFault>(
+    response: &str,
+) -> Result<Result<Output, Error>, crate::Error> {
+    trace!("received: {}", response);
+    let r = match Response::from_xml(response) {
+        Err(e @ RpcError::Fault { .. }) => return fault(e).map(Err),
+        r => r?,
+    };
+    trace!("parsed: {:#?}", r);
+    let o = Output::from_element(&r.body);
+    trace!("output: {:#?}", o);
+
+    o.map(Ok)
+}
+
+/// Decodes the fault of a one-way call, if it failed: successful calls are
+/// answered without an envelope, or with an empty `Body`.
+fn decode_fault<Error: Debug + FromFault>(
+    response: &str,
+) -> Result<Result<(), Error>, crate::Error> {
+    trace!("received: {}", response);
+    if response.trim().is_empty() {
+        return Ok(Ok(()));
+    }
+    match Response::from_xml(response) {
+        Err(e @ RpcError::Fault { .. }) => fault(e).map(Err),
+        Ok(_)
+        | Err(RpcError::XmlError {
+            error: crate::rpser::xml::Error::ExpectedNotEmpty { .. },
+        }) => Ok(Ok(())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The fault of the operation in the `detail` of a SOAP Fault, or the Fault
+/// itself as an error if it is not one of them.
+fn fault<Error: Debug + FromFault>(e: RpcError) -> Result<Error, crate::Error> {
+    let fault = match &e {
+        RpcError::Fault { fault_detail, .. } => Error::from_fault(fault_detail),
+        _ => None,
+    };
+    trace!("fault: {:#?}", fault);
 
+    match fault {
+        Some(fault) => fault,
+        None => Err(e.into()),
+    }
+}
+
+pub async fn one_way<Input: ToElements>(
+    client: &Client,
+    base_url: &str,
+    ns: &str,
+    method: &str,
+    input: &Input,
+) -> Result<(), crate::Error> {
     let response: String = client
         .post(base_url)
         .header("Content-Type", "text/xml")
         .header("MessageType", "Call")
-        .body(s)
+        .body(envelope(ns, method, input))
         .send()
         .await?
         .text()
@@ -33,37 +88,115 @@ pub async fn one_way<Input: ToElements>(
     Ok(())
 }
 
-pub async fn request_response<Input: ToElements, Output: Debug + FromElement, Error>(
+/// Sends a one-way message to an operation which declares faults, decoding
+/// the fault it may be answered with.
+pub async fn one_way_with_faults<Input: ToElements, Error: Debug + FromFault>(
     client: &Client,
     base_url: &str,
     ns: &str,
     method: &str,
     input: &Input,
-) -> Result<Result<Output, Error>, crate::Error> {
-    let mut v = input.to_elements();
-    let mut m = Method::new(method);
+) -> Result<Result<(), Error>, crate::Error> {
+    let response: String = client
+        .post(base_url)
+        .header("Content-Type", "text/xml")
+        .header("MessageType", "Call")
+        .body(envelope(ns, method, input))
+        .send()
+        .await?
+        .text()
+        .await?;
 
-    for el in v.drain(..) {
-        m = m.with(el);
-    }
-    let s = m.as_xml(ns);
-    trace!("sending: {}", s);
+    decode_fault(&response)
+}
 
+pub async fn request_response<
+    Input: ToElements,
+    Output: Debug + FromElement,
+    Error: Debug + FromFault,
+>(
+    client: &Client,
+    base_url: &str,
+    ns: &str,
+    method: &str,
+    input: &Input,
+) -> Result<Result<Output, Error>, crate::Error> {
     let response: String = client
         .post(base_url)
         .header("Content-Type", "text/xml")
         .header("MessageType", "Call")
-        .body(s)
+        .body(envelope(ns, method, input))
         .send()
         .await?
         .text()
         .await?;
 
-    trace!("received: {}", response);
-    let r = Response::from_xml(&response).unwrap();
-    trace!("parsed: {:#?}", r);
-    let o = Output::from_element(&r.body);
-    trace!("output: {:#?}", o);
+    decode(&response)
+}
 
-    o.map(Ok)
+/// Blocking calls, used by clients generated with `Generator::blocking`.
+pub mod blocking {
+    use super::{decode, decode_fault, envelope};
+    use crate::gen::{FromElement, FromFault, ToElements};
+    use reqwest::blocking::Client;
+    use std::fmt::Debug;
+
+    pub fn one_way<Input: ToElements>(
+        client: &Client,
+        base_url: &str,
+        ns: &str,
+        method: &str,
+        input: &Input,
+    ) -> Result<(), crate::Error> {
+        let response: String = client
+            .post(base_url)
+            .header("Content-Type", "text/xml")
+            .header("MessageType", "Call")
+            .body(envelope(ns, method, input))
+            .send()?
+            .text()?;
+
+        trace!("received: {}", response);
+        Ok(())
+    }
+
+    pub fn one_way_with_faults<Input: ToElements, Error: Debug + FromFault>(
+        client: &Client,
+        base_url: &str,
+        ns: &str,
+        method: &str,
+        input: &Input,
+    ) -> Result<Result<(), Error>, crate::Error> {
+        let response: String = client
+            .post(base_url)
+            .header("Content-Type", "text/xml")
+            .header("MessageType", "Call")
+            .body(envelope(ns, method, input))
+            .send()?
+            .text()?;
+
+        decode_fault(&response)
+    }
+
+    pub fn request_response<
+        Input: ToElements,
+        Output: Debug + FromElement,
+        Error: Debug + FromFault,
+    >(
+        client: &Client,
+        base_url: &str,
+        ns: &str,
+        method: &str,
+        input: &Input,
+    ) -> Result<Result<Output, Error>, crate::Error> {
+        let response: String = client
+            .post(base_url)
+            .header("Content-Type", "text/xml")
+            .header("MessageType", "Call")
+            .body(envelope(ns, method, input))
+            .send()?
+            .text()?;
+
+        decode(&response)
+    }
 }
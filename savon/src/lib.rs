@@ -1,9 +1,5 @@
 #[macro_use]
 extern crate log;
-extern crate proc_macro;
-extern crate proc_macro2;
-#[macro_use]
-extern crate quote;
 
 // reexport dependencies so they can be used from the generated code
 // without requiring more imports
@@ -11,14 +7,17 @@ extern crate quote;
 pub mod internal {
     pub use chrono;
     pub use reqwest;
+    #[cfg(feature = "serde")]
+    pub use serde;
     pub use xmltree;
 }
 
+pub mod bounded;
 mod error;
 pub mod gen;
 pub mod http;
 pub mod rpser;
-pub mod string;
-pub mod wsdl;
 
 pub use error::*;
+pub use savon_gen::{string, wsdl, xsd};
+pub use savon_macros::{from_wsdl, wsdl};
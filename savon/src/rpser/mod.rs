@@ -2,12 +2,17 @@
 
 pub mod xml;
 
+use std::collections::HashMap;
 use std::fmt;
 use std::result;
 
 use self::xml::BuildElement;
 use xmltree::Element;
 
+const SOAP11_ENVELOPE_NS: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+const SOAP12_ENVELOPE_NS: &str = "http://www.w3.org/2003/05/soap-envelope";
+const SOAP_ENCODING_NS: &str = "http://schemas.xmlsoap.org/soap/encoding/";
+
 /// XML method representation.
 #[derive(Debug)]
 pub struct Method {
@@ -33,24 +38,94 @@ impl Method {
     }
 
     /// Convert method to full XML envelope.
+    ///
+    /// Arguments with a namespace (see `BuildElement::with_namespace`) are
+    /// prefixed, `api_url` being bound to `ns` and any other namespace to
+    /// `ns1`, `ns2`, etc. declared on the envelope.
     pub fn as_xml(&self, api_url: &str) -> String {
         let namespace = "ns";
 
-        let envelope = Element::node("soap:Envelope")
-            .with_attr("xmlns:soap", "http://schemas.xmlsoap.org/soap/envelope/")
-            .with_attr(format!("xmlns:{}", namespace), api_url)
-            .with_children(vec![
-                Element::node("soap:Header"),
-                Element::node("soap:Body").with_child(
-                    Element::node(format!("{}:{}", namespace, self.name))
-                        .with_children_from_iter(self.args.iter()),
-                ),
-            ]);
+        let mut namespaces = vec![api_url.to_string()];
+        let mut args = self.args.iter().map(|a| a.cloned()).collect::<Vec<_>>();
+        for arg in args.iter_mut() {
+            qualify(arg, &mut namespaces);
+        }
+
+        let mut envelope = Element::node("soap:Envelope")
+            .with_attr("xmlns:soap", SOAP11_ENVELOPE_NS)
+            .with_attr("xmlns:xsi", xml::XSI_NS)
+            .with_attr(format!("xmlns:{}", namespace), api_url);
+        for (i, ns) in namespaces.iter().enumerate().skip(1) {
+            envelope = envelope.with_attr(format!("xmlns:{}{}", namespace, i), ns);
+        }
+
+        let envelope = envelope.with_children(vec![
+            Element::node("soap:Header"),
+            Element::node("soap:Body").with_child(
+                Element::node(format!("{}:{}", namespace, self.name)).with_children(args),
+            ),
+        ]);
 
         envelope.to_string()
     }
 }
 
+/// Assign a prefix to the namespaced elements of the tree, the prefix of the
+/// namespace at index `i` of `namespaces` being `ns{i}` (`ns` for 0).
+pub(crate) fn qualify(element: &mut Element, namespaces: &mut Vec<String>) {
+    let mut prefix = |ns: &str| {
+        let index = match namespaces.iter().position(|n| n == ns) {
+            Some(index) => index,
+            None => {
+                namespaces.push(ns.to_string());
+                namespaces.len() - 1
+            }
+        };
+
+        match index {
+            0 => "ns".to_string(),
+            i => format!("ns{}", i),
+        }
+    };
+
+    if let (Some(ns), None) = (element.namespace.as_ref(), element.prefix.as_ref()) {
+        element.prefix = Some(prefix(ns));
+    }
+
+    // `xsi:type` values are written as `{namespace}name`
+    if let Some(xsi_type) = element.attributes.get_mut("xsi:type") {
+        if let Some((ns, name)) = xsi_type.strip_prefix('{').and_then(|t| t.split_once('}')) {
+            *xsi_type = format!("{}:{}", prefix(ns), name);
+        }
+    }
+
+    // and qualified attributes are keyed by `{namespace}name`, see `xml::parse`
+    let qualified = element
+        .attributes
+        .keys()
+        .filter(|key| key.starts_with('{'))
+        .cloned()
+        .collect::<Vec<_>>();
+    for key in qualified {
+        let value = element.attributes.remove(&key).unwrap_or_default();
+        if let (Some(ns), name) = xml::split_attribute_key(&key) {
+            let prefix = match xml::attribute_prefix(ns) {
+                Some(prefix) => prefix.to_string(),
+                None => prefix(ns),
+            };
+            element.attributes.insert(format!("{prefix}:{name}"), value);
+        }
+    }
+
+    for child in element
+        .children
+        .iter_mut()
+        .filter_map(|c| c.as_mut_element())
+    {
+        qualify(child, namespaces);
+    }
+}
+
 /// XML response representation.
 #[derive(Debug)]
 pub struct Response {
@@ -60,35 +135,131 @@ pub struct Response {
 impl Response {
     /// Parse response from XML.
     pub fn from_xml(xml: &str) -> Result<Response> {
-        let mut bytes = xml.as_bytes();
-        let mut element = Element::parse(&mut bytes).unwrap();
+        let mut element = xml::parse(xml.as_bytes()).map_err(|e| RpcError::MalformedXml {
+            message: e.to_string(),
+        })?;
 
-        if element.name != "Envelope" {
-            return Err(RpcError::UnexpectedElement { tag: element.name });
-        }
-        element = element.descend(&["Body"])?;
+        let envelope_ns = match element.namespace.as_deref() {
+            Some(ns @ (SOAP11_ENVELOPE_NS | SOAP12_ENVELOPE_NS)) if element.name == "Envelope" => {
+                ns.to_string()
+            }
+            _ => return Err(RpcError::UnexpectedElement { tag: element.name }),
+        };
+        element = element.take_child(("Body", envelope_ns.as_str())).ok_or(
+            xml::Error::NotFoundAtPath {
+                path: vec!["Body".into()],
+            },
+        )?;
+        resolve_references(&mut element);
         element = element.descend_first()?;
 
-        if element.name == "Fault" {
-            return Err(RpcError::Fault {
-                fault_code: element
-                    .get_at_path(&["faultcode"])?
-                    .get_text()
-                    .map(|t| t.to_string())
-                    .unwrap_or_default(),
-                fault_string: element
-                    .get_at_path(&["faultstring"])?
-                    .get_text()
-                    .map(|t| t.to_string())
-                    .unwrap_or_default(),
-                fault_detail: Box::new(element.get_at_path(&["detail"])?),
-            });
+        if element.name == "Fault" && element.namespace.as_deref() == Some(&envelope_ns) {
+            return Err(fault(&element, &envelope_ns)?);
         }
 
         Ok(Response { body: element })
     }
 }
 
+/// Decodes a `Fault` of the given envelope namespace: its children are
+/// `faultcode`, `faultstring` and `detail` in SOAP 1.1, and `Code/Value`,
+/// `Reason/Text` and `Detail` in SOAP 1.2. A fault without details is given
+/// an empty one.
+fn fault(element: &Element, envelope_ns: &str) -> Result<RpcError> {
+    let (code, string, detail) = match envelope_ns {
+        SOAP12_ENVELOPE_NS => (&["Code", "Value"][..], &["Reason", "Text"][..], "Detail"),
+        _ => (&["faultcode"][..], &["faultstring"][..], "detail"),
+    };
+    let text = |path: &[&str]| -> Result<String> {
+        Ok(element
+            .get_at_path(path)?
+            .get_text()
+            .map(|t| t.to_string())
+            .unwrap_or_default())
+    };
+
+    Ok(RpcError::Fault {
+        fault_code: text(code)?,
+        fault_string: text(string)?,
+        fault_detail: Box::new(
+            element
+                .get_at_path(&[detail])
+                .unwrap_or_else(|_| Element::new(detail)),
+        ),
+    })
+}
+
+/// Whether `element` is an independent, SOAP-encoded multi-reference value,
+/// e.g. Axis' `<multiRef id="id0" soapenc:root="0">`.
+fn is_multi_ref(element: &Element) -> bool {
+    element.attributes.contains_key("id")
+        && (element.name == "multiRef"
+            || element
+                .attributes
+                .get(&xml::attribute_key(SOAP_ENCODING_NS, "root"))
+                .map(|s| s.as_str())
+                == Some("0"))
+}
+
+/// Inline SOAP-encoded multi-reference values.
+///
+/// The multiRef elements are removed from the `Body` and every accessor
+/// pointing to them with `href="#id"` receives their attributes and children,
+/// so the deserializer only has to handle a plain tree. Unknown references
+/// are left untouched.
+fn resolve_references(body: &mut Element) {
+    let mut refs = HashMap::new();
+    body.children.retain(|child| match child.as_element() {
+        Some(e) if is_multi_ref(e) => {
+            refs.insert(e.attributes["id"].clone(), e.clone());
+            false
+        }
+        _ => true,
+    });
+
+    if !refs.is_empty() {
+        dereference(body, &refs, &mut Vec::new());
+    }
+}
+
+fn dereference(element: &mut Element, refs: &HashMap<String, Element>, stack: &mut Vec<String>) {
+    for child in element
+        .children
+        .iter_mut()
+        .filter_map(|c| c.as_mut_element())
+    {
+        let id = match child.attributes.get("href") {
+            Some(href) if href.starts_with('#') => href[1..].to_string(),
+            _ => {
+                dereference(child, refs, stack);
+                continue;
+            }
+        };
+
+        // a cycle cannot be represented as a tree, keep the reference as is
+        if stack.contains(&id) {
+            continue;
+        }
+        if let Some(target) = refs.get(&id) {
+            child.attributes.remove("href");
+            let root = xml::attribute_key(SOAP_ENCODING_NS, "root");
+            for (k, v) in target.attributes.iter() {
+                if *k != "id" && *k != root {
+                    child
+                        .attributes
+                        .entry(k.clone())
+                        .or_insert_with(|| v.clone());
+                }
+            }
+            child.children = target.children.clone();
+
+            stack.push(id);
+            dereference(child, refs, stack);
+            stack.pop();
+        }
+    }
+}
+
 impl fmt::Display for Method {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{} {:?}", self.name, self.args)
@@ -112,6 +283,10 @@ pub enum RpcError {
     UnexpectedElement {
         tag: String,
     },
+    /// The response is not well-formed XML, e.g. an HTML error page.
+    MalformedXml {
+        message: String,
+    },
     ElementWasEmpty {
         name: String,
     },
@@ -166,6 +341,292 @@ mod test {
         };
     }
 
+    #[test]
+    fn can_deal_with_soap12_fault() {
+        let faulty_response = r#"<?xml version="1.0" encoding="utf-8"?>
+            <env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope">
+                <env:Body>
+                    <env:Fault>
+                        <env:Code>
+                            <env:Value>env:Sender</env:Value>
+                            <env:Subcode><env:Value>m:UnknownCity</env:Value></env:Subcode>
+                        </env:Code>
+                        <env:Reason>
+                            <env:Text xml:lang="en">Unknown city</env:Text>
+                        </env:Reason>
+                        <env:Detail>
+                            <m:UnknownCity xmlns:m="http://tempuri.org/weather">Atlantis</m:UnknownCity>
+                        </env:Detail>
+                    </env:Fault>
+                </env:Body>
+            </env:Envelope>
+        "#;
+
+        match Response::from_xml(faulty_response) {
+            Err(RpcError::Fault {
+                fault_code,
+                fault_string,
+                fault_detail,
+            }) => {
+                assert_eq!(fault_code, "env:Sender");
+                assert_eq!(fault_string, "Unknown city");
+                let entry = fault_detail.get_at_path(&["UnknownCity"]).unwrap();
+                assert_eq!(entry.get_text().unwrap(), "Atlantis");
+            }
+            other => panic!(
+                "expected to receive fault in this test, received {:?}",
+                other
+            ),
+        };
+
+        // the detail is optional
+        let faulty_response = faulty_response.replace(
+            r#"<env:Detail>
+                            <m:UnknownCity xmlns:m="http://tempuri.org/weather">Atlantis</m:UnknownCity>
+                        </env:Detail>"#,
+            "",
+        );
+        match Response::from_xml(&faulty_response) {
+            Err(RpcError::Fault { fault_detail, .. }) => {
+                assert!(fault_detail.children.is_empty())
+            }
+            other => panic!(
+                "expected to receive fault in this test, received {:?}",
+                other
+            ),
+        };
+    }
+
+    #[test]
+    fn rejects_malformed_responses() {
+        let html = "<html><body><h1>502 Bad Gateway</h1></html>";
+        assert!(matches!(
+            Response::from_xml(html),
+            Err(RpcError::MalformedXml { .. })
+        ));
+    }
+
+    #[test]
+    fn qualifies_namespaced_arguments() {
+        let xml = Method::new("GetQuote")
+            .with(
+                Element::node("symbol")
+                    .with_namespace("urn:quotes")
+                    .with_text("SOAP"),
+            )
+            .with(
+                Element::node("currency")
+                    .with_namespace("urn:currencies")
+                    .with_child(Element::node("code").with_text("EUR")),
+            )
+            .as_xml("urn:quotes");
+
+        assert!(xml.contains(r#"xmlns:ns="urn:quotes""#));
+        assert!(xml.contains(r#"xmlns:ns1="urn:currencies""#));
+        assert!(xml.contains("<ns:GetQuote><ns:symbol>SOAP</ns:symbol><ns1:currency><code>EUR</code></ns1:currency></ns:GetQuote>"));
+    }
+
+    #[test]
+    fn qualifies_xsi_types() {
+        let xml = Method::new("Pay")
+            .with(
+                Element::node("payment")
+                    .with_attr("xsi:type", "{urn:payments}CreditCardPayment")
+                    .with_child(Element::node("amount").with_text("10")),
+            )
+            .as_xml("urn:shop");
+
+        assert!(xml.contains(r#"xmlns:ns1="urn:payments""#));
+        assert!(xml.contains(r#"<payment xsi:type="ns1:CreditCardPayment">"#));
+
+        let response = r#"<?xml version="1.0" encoding="utf-8"?>
+            <soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"
+                           xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+                <soap:Body>
+                    <payment xmlns:p="urn:payments" xsi:type="p:CreditCardPayment"/>
+                </soap:Body>
+            </soap:Envelope>
+        "#;
+        let body = Response::from_xml(response).unwrap().body;
+        assert_eq!(
+            body.get_xsi_type(),
+            Some(("urn:payments".to_string(), "CreditCardPayment".to_string()))
+        );
+    }
+
+    #[test]
+    fn ignores_unqualified_type_attributes() {
+        let response = r#"<?xml version="1.0" encoding="utf-8"?>
+            <soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"
+                           xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+                <soap:Body>
+                    <payment xmlns:p="urn:payments" type="p:CreditCardPayment"/>
+                </soap:Body>
+            </soap:Envelope>
+        "#;
+        let body = Response::from_xml(response).unwrap().body;
+        assert_eq!(body.get_xsi_type(), None);
+        assert_eq!(body.attributes["type"], "p:CreditCardPayment");
+    }
+
+    #[test]
+    fn resolves_multi_refs() {
+        let encoded_response = r##"<?xml version="1.0" encoding="utf-8"?>
+            <soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns:soapenc="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+                <soapenv:Body>
+                    <ns1:getSpacesResponse soapenv:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/" xmlns:ns1="urn:confluence">
+                        <getSpacesReturn soapenc:arrayType="ns1:RemoteSpace[2]" xsi:type="soapenc:Array">
+                            <getSpacesReturn href="#id0"/>
+                            <getSpacesReturn href="#id1"/>
+                        </getSpacesReturn>
+                    </ns1:getSpacesResponse>
+                    <multiRef id="id1" soapenc:root="0" xsi:type="ns1:RemoteSpace" xmlns:ns1="urn:confluence">
+                        <key xsi:type="xsd:string">DOC</key>
+                        <owner href="#id2"/>
+                    </multiRef>
+                    <multiRef id="id0" soapenc:root="0" xsi:type="ns1:RemoteSpace" xmlns:ns1="urn:confluence">
+                        <key xsi:type="xsd:string">DEV</key>
+                        <owner href="#id2"/>
+                    </multiRef>
+                    <multiRef id="id2" soapenc:root="0" xsi:type="xsd:string">admin</multiRef>
+                </soapenv:Body>
+            </soapenv:Envelope>
+        "##;
+
+        let response = Response::from_xml(encoded_response).unwrap();
+        assert_eq!(response.body.name, "getSpacesResponse");
+
+        let spaces = response.body.get_at_path(&["getSpacesReturn"]).unwrap();
+        let keys = spaces
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .map(|space| {
+                assert_eq!(
+                    space.get_xsi_type(),
+                    Some(("urn:confluence".to_string(), "RemoteSpace".to_string()))
+                );
+                assert_eq!(
+                    space.get_at_path(&["owner"]).unwrap().get_text().unwrap(),
+                    "admin"
+                );
+                space
+                    .get_at_path(&["key"])
+                    .unwrap()
+                    .get_text()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["DEV", "DOC"]);
+    }
+
+    #[test]
+    fn keeps_undeclared_content() {
+        let response = r#"<?xml version="1.0" encoding="utf-8"?>
+            <soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"
+                           xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+                <soap:Body>
+                    <Address xmlns="urn:addresses" xmlns:ext="urn:ext" xsi:type="Address" ext:verified="true">
+                        <Street>Main St</Street>
+                        <ext:Geo>51.5,-0.1</ext:Geo>
+                    </Address>
+                </soap:Body>
+            </soap:Envelope>
+        "#;
+
+        let body = Response::from_xml(response).unwrap().body;
+        let declared = [(xml::NamespaceMatch::Qualified("urn:addresses"), "Street")];
+        let children = body.get_undeclared_children(&declared);
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "Geo");
+        assert_eq!(children[0].namespace.as_deref(), Some("urn:ext"));
+
+        let attributes = body.get_undeclared_attributes(&[]);
+        assert_eq!(attributes.len(), 1);
+        assert_eq!(attributes["{urn:ext}verified"], "true");
+    }
+
+    #[test]
+    fn collects_occurrences_by_name() {
+        let element = Element::node("tCountryInfo")
+            .with_child(Element::node("sISOCode").with_text("BE"))
+            .with_child(Element::node("Languages").with_text("nl"))
+            .with_child(Element::node("sName").with_text("Belgium"))
+            .with_child(Element::node("Languages").with_text("fr"));
+
+        let any = xml::NamespaceMatch::Any;
+        let languages = element
+            .get_occurrences(any, "Languages", 0, None)
+            .unwrap()
+            .iter()
+            .map(|e| e.get_text().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(languages, vec!["nl", "fr"]);
+
+        assert_eq!(
+            element.get_occurrences(any, "Languages", 3, None),
+            Err(xml::Error::UnexpectedOccurrences {
+                name: "Languages".to_string(),
+                found: 2,
+                min: 3,
+                max: None,
+            })
+        );
+        assert!(element
+            .get_occurrences(any, "Languages", 0, Some(1))
+            .is_err());
+        assert!(element
+            .get_occurrences(any, "sPhoneCode", 0, Some(1))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn matches_namespaces() {
+        let response = r#"<?xml version="1.0" encoding="utf-8"?>
+            <soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+                <soap:Body>
+                    <GetQuoteResponse xmlns="urn:quotes">
+                        <Price>12.5</Price>
+                        <Price xmlns="urn:other">13</Price>
+                        <Price xmlns="">14</Price>
+                    </GetQuoteResponse>
+                </soap:Body>
+            </soap:Envelope>
+        "#;
+
+        let body = Response::from_xml(response).unwrap().body;
+        let prices = |namespace| {
+            body.get_occurrences(namespace, "Price", 0, None)
+                .unwrap()
+                .iter()
+                .map(|e| e.get_text().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            prices(xml::NamespaceMatch::Qualified("urn:quotes")),
+            vec!["12.5"]
+        );
+        assert_eq!(
+            prices(xml::NamespaceMatch::Qualified("urn:other")),
+            vec!["13"]
+        );
+        assert_eq!(prices(xml::NamespaceMatch::Unqualified), vec!["14"]);
+        assert_eq!(prices(xml::NamespaceMatch::Any), vec!["12.5", "13", "14"]);
+
+        let wrong_envelope = response.replace(
+            "http://schemas.xmlsoap.org/soap/envelope/",
+            "http://example.com/envelope/",
+        );
+        assert_eq!(
+            Response::from_xml(&wrong_envelope).unwrap_err(),
+            RpcError::UnexpectedElement {
+                tag: "Envelope".to_string()
+            }
+        );
+    }
+
     #[test]
     fn returns_result_element() {
         let ok_response = r#"<?xml version="1.0" encoding="utf-8"?>
@@ -2,9 +2,96 @@
 
 use chrono::offset::Utc;
 use chrono::{DateTime, ParseError};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::num::ParseIntError;
-use xmltree::Element;
+use xmltree::{Element, XMLNode};
+
+/// Namespace of the `xsi:type` and `xsi:nil` attributes.
+pub const XSI_NS: &str = "http://www.w3.org/2001/XMLSchema-instance";
+/// Namespace bound to the reserved `xml` prefix, as in `xml:lang`.
+const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Key of an attribute in `namespace` in the attributes of an element, its
+/// expanded name `{namespace}name`.
+pub fn attribute_key(namespace: &str, name: &str) -> String {
+    format!("{{{namespace}}}{name}")
+}
+
+/// Splits the key of an attribute into its namespace, if it is qualified,
+/// and its local name.
+pub fn split_attribute_key(key: &str) -> (Option<&str>, &str) {
+    match key.strip_prefix('{').and_then(|k| k.split_once('}')) {
+        Some((namespace, name)) => (Some(namespace), name),
+        None => (None, key),
+    }
+}
+
+/// Parses an XML document into its root element, like
+/// `xmltree::Element::parse`, except for the keys of the attributes: `xmltree`
+/// keys them by local name, losing the namespace of prefixed ones, which are
+/// keyed by `attribute_key` here. Unprefixed attributes, in no namespace, are
+/// still keyed by their local name.
+pub fn parse(xml: &[u8]) -> Result<Element, xmltree::ParseError> {
+    use xml_rs::reader::{EventReader, XmlEvent};
+
+    let mut stack: Vec<Element> = vec![];
+    for event in EventReader::new(xml) {
+        match event.map_err(xmltree::ParseError::MalformedXml)? {
+            XmlEvent::StartElement {
+                name,
+                attributes,
+                namespace,
+            } => {
+                let attributes = attributes
+                    .into_iter()
+                    .map(|attribute| {
+                        let key = match (&attribute.name.prefix, &attribute.name.namespace) {
+                            (Some(_), Some(namespace)) => {
+                                attribute_key(namespace, &attribute.name.local_name)
+                            }
+                            _ => attribute.name.local_name,
+                        };
+                        (key, attribute.value)
+                    })
+                    .collect();
+
+                stack.push(Element {
+                    prefix: name.prefix,
+                    namespace: name.namespace,
+                    namespaces: (!namespace.is_essentially_empty()).then_some(namespace),
+                    name: name.local_name,
+                    attributes,
+                    children: vec![],
+                });
+            }
+            XmlEvent::EndElement { .. } => {
+                let element = stack.pop().ok_or(xmltree::ParseError::CannotParse)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(XMLNode::Element(element)),
+                    None => return Ok(element),
+                }
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(XMLNode::Text(text));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(xmltree::ParseError::CannotParse)
+}
+
+/// Prefix of a namespace when writing a qualified attribute, `xsi` and `xml`
+/// being declared on every document.
+pub(crate) fn attribute_prefix(namespace: &str) -> Option<&'static str> {
+    match namespace {
+        XSI_NS => Some("xsi"),
+        XML_NS => Some("xml"),
+        _ => None,
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -22,10 +109,55 @@ pub enum Error {
     ParseIntError { name: String, inner: ParseIntError },
     /// Can't parse received element.
     ParseDateTimeError { name: String, inner: ParseError },
+    /// Element occurred fewer or more times than allowed by the schema.
+    UnexpectedOccurrences {
+        name: String,
+        found: usize,
+        min: u32,
+        max: Option<u32>,
+    },
+    /// Required attribute is missing.
+    AttributeNotFound { name: String, attribute: String },
+    /// Element or attribute differs from the value fixed by the schema.
+    UnexpectedFixedValue {
+        name: String,
+        expected: String,
+        given: String,
+    },
+    /// Value is not valid for any member type of a union.
+    UnexpectedUnionValue { name: String, given: String },
+    /// Root element of a document is not the expected one. Names in a
+    /// namespace are written `{namespace}name`.
+    UnexpectedRootElement { expected: String, given: String },
+    /// Value cannot be converted to the Rust type it is mapped to.
+    Conversion { given: String, message: String },
     /// empty
     Empty,
 }
 
+/// Namespace a child element is expected in when decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamespaceMatch<'a> {
+    /// The element must be in this namespace.
+    Qualified(&'a str),
+    /// The element must not be in any namespace.
+    Unqualified,
+    /// Only the local name is compared, for servers getting namespaces wrong.
+    Any,
+}
+
+impl NamespaceMatch<'_> {
+    /// Whether `element` is named `name` in the expected namespace.
+    pub fn matches(&self, element: &Element, name: &str) -> bool {
+        element.name == name
+            && match self {
+                NamespaceMatch::Qualified(ns) => element.namespace.as_deref() == Some(*ns),
+                NamespaceMatch::Unqualified => element.namespace.is_none(),
+                NamespaceMatch::Any => true,
+            }
+    }
+}
+
 /// Helper trait for building `xmltree::Element`.
 ///
 /// Such convenience methods were not available in `xmltree::Element`, so they are added
@@ -59,6 +191,10 @@ pub trait BuildElement {
         S: Into<String>;
     /// Modify node's name.
     fn with_name<S>(self, name: S) -> Self
+    where
+        S: Into<String>;
+    /// Set node's namespace URI, the prefix is assigned when serializing the message.
+    fn with_namespace<S>(self, namespace: S) -> Self
     where
         S: Into<String>;
     /// Modify node's text.
@@ -70,8 +206,18 @@ pub trait BuildElement {
     where
         KS: Into<String>,
         VS: Into<String>;
+    /// Add attributes.
+    fn with_attrs<I, KS, VS>(self, attrs: I) -> Self
+    where
+        I: IntoIterator<Item = (KS, VS)>,
+        KS: Into<String>,
+        VS: Into<String>;
     /// Add child.
     fn with_child(self, child: Self) -> Self;
+    /// Add text and element children.
+    fn with_nodes<I>(self, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = xmltree::XMLNode>;
     /// Add children.
     fn with_children<I>(self, children: I) -> Self
     where
@@ -94,6 +240,54 @@ pub trait BuildElement {
     /// Get clone of child element at path.
     fn get_at_path(&self, path: &[&str]) -> Result<Element, Error>;
 
+    /// Get all child elements named `name` in the `namespace`, in document
+    /// order, checking that there are between `min` and `max` (`None` for
+    /// unbounded) of them.
+    fn get_occurrences(
+        &self,
+        namespace: NamespaceMatch,
+        name: &str,
+        min: u32,
+        max: Option<u32>,
+    ) -> Result<Vec<&Element>, Error>;
+
+    /// Get the child elements matching any of `names`, in document order,
+    /// checking that there are between `min` and `max` of them, for the
+    /// members of a substitution group.
+    fn get_occurrences_of(
+        &self,
+        names: &[(NamespaceMatch, &str)],
+        min: u32,
+        max: Option<u32>,
+    ) -> Result<Vec<&Element>, Error>;
+
+    /// Whether the element is explicitly nil (`xsi:nil="true"`).
+    fn is_nil(&self) -> bool;
+
+    /// Get the value of a required attribute.
+    fn get_attribute(&self, name: &str) -> Result<&str, Error>;
+
+    /// Check the text of the element is the value fixed by the schema.
+    fn check_fixed(&self, fixed: &str) -> Result<(), Error>;
+
+    /// Check the attribute, if present, has the value fixed by the schema.
+    fn check_fixed_attribute(&self, name: &str, fixed: &str) -> Result<(), Error>;
+
+    /// Namespace and local name of the `xsi:type` of the element, the
+    /// namespace being empty if the prefix is not declared. Only the `type`
+    /// attribute of the XMLSchema-instance namespace is considered, not an
+    /// attribute of the element named `type`.
+    fn get_xsi_type(&self) -> Option<(String, String)>;
+
+    /// Clones of the child elements matching none of the `declared` ones, for
+    /// `<any/>` wildcards.
+    fn get_undeclared_children(&self, declared: &[(NamespaceMatch, &str)]) -> Vec<Element>;
+
+    /// Attributes of the element except the `declared` ones, `xsi:nil` and
+    /// `xsi:type`, for `<anyAttribute/>` wildcards. Qualified attributes are
+    /// keyed by `attribute_key`, and written back in their namespace.
+    fn get_undeclared_attributes(&self, declared: &[&str]) -> BTreeMap<String, String>;
+
     /// Extract the value of `long` type from the text.
     fn as_long(&self) -> Result<i64, Error>;
 
@@ -146,6 +340,14 @@ impl BuildElement for Element {
         self
     }
 
+    fn with_namespace<S>(mut self, namespace: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
     fn with_text<S>(mut self, text: S) -> Self
     where
         S: Into<String>,
@@ -163,11 +365,30 @@ impl BuildElement for Element {
         self
     }
 
+    fn with_attrs<I, KS, VS>(mut self, attrs: I) -> Self
+    where
+        I: IntoIterator<Item = (KS, VS)>,
+        KS: Into<String>,
+        VS: Into<String>,
+    {
+        self.attributes
+            .extend(attrs.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
     fn with_child(mut self, child: Self) -> Self {
         self.children.push(xmltree::XMLNode::Element(child));
         self
     }
 
+    fn with_nodes<I>(mut self, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = xmltree::XMLNode>,
+    {
+        self.children.extend(nodes);
+        self
+    }
+
     fn with_children<I>(mut self, children: I) -> Self
     where
         I: IntoIterator<Item = Self>,
@@ -250,6 +471,127 @@ impl BuildElement for Element {
         }
     }
 
+    fn get_occurrences(
+        &self,
+        namespace: NamespaceMatch,
+        name: &str,
+        min: u32,
+        max: Option<u32>,
+    ) -> Result<Vec<&Element>, Error> {
+        self.get_occurrences_of(&[(namespace, name)], min, max)
+    }
+
+    fn get_occurrences_of(
+        &self,
+        names: &[(NamespaceMatch, &str)],
+        min: u32,
+        max: Option<u32>,
+    ) -> Result<Vec<&Element>, Error> {
+        let elements = self
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| {
+                names
+                    .iter()
+                    .any(|(namespace, name)| namespace.matches(c, name))
+            })
+            .collect::<Vec<_>>();
+
+        let found = elements.len();
+        if found < min as usize || max.map(|max| found > max as usize).unwrap_or(false) {
+            return Err(Error::UnexpectedOccurrences {
+                name: names
+                    .first()
+                    .map(|(_, name)| *name)
+                    .unwrap_or_default()
+                    .into(),
+                found,
+                min,
+                max,
+            });
+        }
+
+        Ok(elements)
+    }
+
+    fn is_nil(&self) -> bool {
+        matches!(
+            self.attributes
+                .get(&attribute_key(XSI_NS, "nil"))
+                .map(|s| s.as_str()),
+            Some("true" | "1")
+        )
+    }
+
+    fn get_attribute(&self, name: &str) -> Result<&str, Error> {
+        self.attributes
+            .get(name)
+            .map(|value| value.as_str())
+            .ok_or_else(|| Error::AttributeNotFound {
+                name: self.name.clone(),
+                attribute: name.into(),
+            })
+    }
+
+    fn check_fixed(&self, fixed: &str) -> Result<(), Error> {
+        let text = self.get_text().unwrap_or_default();
+        if text != fixed {
+            return Err(Error::UnexpectedFixedValue {
+                name: self.name.clone(),
+                expected: fixed.into(),
+                given: text.into(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn check_fixed_attribute(&self, name: &str, fixed: &str) -> Result<(), Error> {
+        match self.attributes.get(name) {
+            Some(value) if value != fixed => Err(Error::UnexpectedFixedValue {
+                name: name.into(),
+                expected: fixed.into(),
+                given: value.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn get_xsi_type(&self) -> Option<(String, String)> {
+        let xsi_type = self.attributes.get(&attribute_key(XSI_NS, "type"))?;
+        let (prefix, name) = xsi_type.split_once(':').unwrap_or(("", xsi_type));
+        let namespace = self
+            .namespaces
+            .as_ref()
+            .and_then(|namespaces| namespaces.get(prefix))
+            .unwrap_or_default();
+
+        Some((namespace.to_string(), name.to_string()))
+    }
+
+    fn get_undeclared_children(&self, declared: &[(NamespaceMatch, &str)]) -> Vec<Element> {
+        self.children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| {
+                !declared
+                    .iter()
+                    .any(|(namespace, name)| namespace.matches(c, name))
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn get_undeclared_attributes(&self, declared: &[&str]) -> BTreeMap<String, String> {
+        self.attributes
+            .iter()
+            .filter(|(key, _)| !matches!(split_attribute_key(key), (Some(XSI_NS), "nil" | "type")))
+            .filter(|(key, _)| !declared.contains(&key.as_str()))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+
     fn as_int(&self) -> Result<i32, Error> {
         let text = get_typed_string(self, "int")?;
         Ok(match text.parse() {
@@ -301,7 +643,10 @@ impl BuildElement for Element {
 
 fn get_typed_string(element: &Element, value_type: &str) -> Result<String, Error> {
     Ok(
-        match (element.attributes.get("type"), &element.get_text()) {
+        match (
+            element.attributes.get(&attribute_key(XSI_NS, "type")),
+            &element.get_text(),
+        ) {
             (Some(value), Some(text)) if value.ends_with(value_type) => text.to_string(),
             (other_type, _) => {
                 return Err(Error::ExpectedElementWithType {